@@ -0,0 +1,10 @@
+ALTER TABLE active_votes.votes
+    ADD COLUMN seats INTEGER NOT NULL DEFAULT 1,
+    ADD COLUMN winner_method TEXT NOT NULL DEFAULT 'bloc_star',
+    ADD CONSTRAINT valid_seats CHECK (seats BETWEEN 1 AND array_length(options, 1)),
+    ADD CONSTRAINT valid_winner_method CHECK (winner_method IN ('bloc_star', 'allocated_score'));
+
+ALTER TABLE archived_votes.votes
+    ADD COLUMN seats INTEGER NOT NULL DEFAULT 1,
+    ADD COLUMN winner_method TEXT NOT NULL DEFAULT 'bloc_star',
+    ADD COLUMN winners TEXT[] NOT NULL DEFAULT '{}';
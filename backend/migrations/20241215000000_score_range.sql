@@ -0,0 +1,40 @@
+ALTER TABLE active_votes.votes
+    ADD COLUMN max_score INTEGER NOT NULL DEFAULT 5,
+    ADD CONSTRAINT valid_max_score CHECK (max_score BETWEEN 1 AND 10);
+
+ALTER TABLE archived_votes.votes
+    ADD COLUMN max_score INTEGER NOT NULL DEFAULT 5;
+
+ALTER TABLE active_votes.ballots DROP CONSTRAINT valid_scores;
+DROP FUNCTION validate_scores(integer[]);
+
+CREATE OR REPLACE FUNCTION validate_scores(scores integer[], max_score integer)
+RETURNS boolean AS $$
+  SELECT NOT EXISTS (
+      SELECT 1 FROM unnest(scores) AS score
+      WHERE score < 0 OR score > max_score
+  );
+$$ LANGUAGE sql IMMUTABLE;
+
+ALTER TABLE active_votes.ballots ADD CONSTRAINT valid_scores CHECK (
+    array_length(scores, 1) > 0
+    AND validate_scores(scores, 10)
+);
+
+CREATE OR REPLACE FUNCTION check_ballot_score_range()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF NOT validate_scores(
+        NEW.scores,
+        (SELECT max_score FROM active_votes.votes WHERE id = NEW.vote_id)
+    ) THEN
+        RAISE EXCEPTION 'Score outside the range allowed for vote %', NEW.vote_id;
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER check_ballot_score_range
+    BEFORE INSERT OR UPDATE ON active_votes.ballots
+    FOR EACH ROW
+    EXECUTE FUNCTION check_ballot_score_range();
@@ -0,0 +1,22 @@
+ALTER TABLE active_votes.votes
+    ADD COLUMN weighted BOOLEAN NOT NULL DEFAULT FALSE;
+
+ALTER TABLE archived_votes.votes
+    ADD COLUMN weighted BOOLEAN NOT NULL DEFAULT FALSE;
+
+ALTER TABLE active_votes.ballots
+    ADD COLUMN weight INTEGER NOT NULL DEFAULT 1 CHECK (weight > 0);
+
+ALTER TABLE archived_votes.ballots
+    ADD COLUMN weight INTEGER NOT NULL DEFAULT 1;
+
+CREATE TABLE active_votes.voter_credentials (
+    id BIGSERIAL PRIMARY KEY,
+    vote_id UUID NOT NULL REFERENCES active_votes.votes(id) ON DELETE CASCADE,
+    token_hash TEXT NOT NULL UNIQUE,
+    weight INTEGER NOT NULL CHECK (weight > 0),
+    issued_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    used_at TIMESTAMPTZ
+);
+
+CREATE INDEX idx_voter_credentials_vote ON active_votes.voter_credentials(vote_id);
@@ -0,0 +1,11 @@
+ALTER TABLE active_votes.votes
+    ADD COLUMN blank_policy TEXT NOT NULL DEFAULT 'count_as_zero',
+    ADD CONSTRAINT valid_blank_policy CHECK (blank_policy IN ('count_as_zero', 'exclude'));
+
+ALTER TABLE archived_votes.votes
+    ADD COLUMN blank_policy TEXT NOT NULL DEFAULT 'count_as_zero';
+
+-- NULL entries in a ballot's scores are "no opinion"; a ballot must still score something.
+ALTER TABLE active_votes.ballots ADD CONSTRAINT scores_not_blank CHECK (
+    cardinality(array_remove(scores, NULL)) > 0
+);
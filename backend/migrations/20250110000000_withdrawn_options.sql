@@ -0,0 +1,12 @@
+ALTER TABLE active_votes.votes
+    ADD COLUMN withdrawn_options TEXT[] NOT NULL DEFAULT '{}',
+    ADD CONSTRAINT valid_withdrawn_options CHECK (withdrawn_options <@ options);
+
+-- winner, winners, head_to_head and final_stats keep the result as originally
+-- counted; the adjusted_* columns hold the recount without withdrawn options.
+ALTER TABLE archived_votes.votes
+    ADD COLUMN withdrawn_options TEXT[] NOT NULL DEFAULT '{}',
+    ADD COLUMN adjusted_winner TEXT,
+    ADD COLUMN adjusted_winners TEXT[],
+    ADD COLUMN adjusted_head_to_head JSONB,
+    ADD COLUMN adjusted_final_stats JSONB;
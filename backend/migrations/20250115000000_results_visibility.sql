@@ -0,0 +1,6 @@
+ALTER TABLE active_votes.votes
+    ADD COLUMN results_visibility TEXT NOT NULL DEFAULT 'hidden_until_close',
+    ADD CONSTRAINT valid_results_visibility CHECK (results_visibility IN ('hidden_until_close', 'live', 'creator_only'));
+
+ALTER TABLE archived_votes.votes
+    ADD COLUMN results_visibility TEXT NOT NULL DEFAULT 'hidden_until_close';
@@ -0,0 +1,4 @@
+-- SHA-256 of the token handed to the creator when the vote is made; NULL for
+-- votes created before management tokens existed.
+ALTER TABLE active_votes.votes
+    ADD COLUMN management_token_hash TEXT;
@@ -0,0 +1,3 @@
+-- Owners keep the right to delete their vote once it is archived.
+ALTER TABLE archived_votes.votes
+    ADD COLUMN management_token_hash TEXT;
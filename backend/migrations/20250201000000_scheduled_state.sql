@@ -0,0 +1,2 @@
+-- On its own so the new value is committed before later migrations use it.
+ALTER TYPE vote_state ADD VALUE 'scheduled' BEFORE 'active';
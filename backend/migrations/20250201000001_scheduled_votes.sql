@@ -0,0 +1,24 @@
+ALTER TABLE active_votes.votes
+    ADD COLUMN opens_at TIMESTAMPTZ,
+    ADD CONSTRAINT opens_before_end CHECK (opens_at IS NULL OR opens_at < voting_ends_at);
+
+ALTER TABLE archived_votes.votes
+    ADD COLUMN opens_at TIMESTAMPTZ;
+
+-- Scheduled votes count towards a user's limit as well.
+CREATE OR REPLACE FUNCTION check_user_vote_limit()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF (
+        SELECT COUNT(*) 
+        FROM active_votes.votes 
+        WHERE user_fingerprint = NEW.user_fingerprint 
+        AND state IN ('scheduled', 'active')
+    ) >= 30 THEN
+        RAISE EXCEPTION 'User has reached the maximum limit of active votes';
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE INDEX idx_active_votes_opens ON active_votes.votes(opens_at) WHERE state = 'scheduled';
@@ -0,0 +1,10 @@
+-- Only public votes are listed; private ones also need an access code, of which only a hash is kept.
+ALTER TABLE active_votes.votes
+    ADD COLUMN visibility TEXT NOT NULL DEFAULT 'public',
+    ADD COLUMN access_code_hash TEXT,
+    ADD CONSTRAINT valid_visibility CHECK (visibility IN ('public', 'unlisted', 'private')),
+    ADD CONSTRAINT private_needs_access_code CHECK ((visibility = 'private') = (access_code_hash IS NOT NULL));
+
+ALTER TABLE archived_votes.votes
+    ADD COLUMN visibility TEXT NOT NULL DEFAULT 'public',
+    ADD COLUMN access_code_hash TEXT;
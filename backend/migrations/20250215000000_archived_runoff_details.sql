@@ -0,0 +1,5 @@
+-- Kept so a recount can check the whole runoff, not just its final round.
+-- NULL on votes archived before these were stored.
+ALTER TABLE archived_votes.votes
+    ADD COLUMN runner_up_matchups JSONB,
+    ADD COLUMN tiebreak JSONB;
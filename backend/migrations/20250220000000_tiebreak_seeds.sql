@@ -0,0 +1,7 @@
+-- Seeds the random-lot tiebreak. Drawn when the vote is created and kept
+-- secret until voting ends; NULL for older votes, which draw from their ID.
+ALTER TABLE active_votes.votes
+    ADD COLUMN tiebreak_seed BIGINT;
+
+ALTER TABLE archived_votes.votes
+    ADD COLUMN tiebreak_seed BIGINT;
@@ -0,0 +1,7 @@
+-- Ballots are read in `id` order a chunk at a time; with `id` in the index each
+-- chunk is a range scan. The new indexes also cover lookups by `vote_id` alone.
+CREATE INDEX idx_active_ballots_vote_id ON active_votes.ballots(vote_id, id);
+CREATE INDEX idx_archived_ballots_vote_id ON archived_votes.ballots(vote_id, id);
+
+DROP INDEX active_votes.idx_active_ballots_vote;
+DROP INDEX archived_votes.idx_archived_ballots_vote;
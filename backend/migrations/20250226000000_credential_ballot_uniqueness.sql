@@ -0,0 +1,9 @@
+-- A weighted ballot is limited by its single-use credential rather than by the
+-- device it came from, so several credential holders may share a fingerprint.
+ALTER TABLE active_votes.ballots
+    ADD COLUMN credential_id BIGINT UNIQUE REFERENCES active_votes.voter_credentials(id);
+
+ALTER TABLE active_votes.ballots DROP CONSTRAINT unique_voter;
+
+CREATE UNIQUE INDEX unique_voter ON active_votes.ballots(vote_id, user_fingerprint)
+    WHERE credential_id IS NULL;
@@ -19,7 +19,7 @@ impl Fairing for CORS {
         if origin.starts_with("http://localhost") {
             res.set_header(Header::new("Access-Control-Allow-Origin", origin));
             res.set_header(Header::new("Access-Control-Allow-Methods", "POST, GET, PATCH, OPTIONS, DELETE"));
-            res.set_header(Header::new("Access-Control-Allow-Headers", "Content-Type, Authorization, X-CSRF-Token"));
+            res.set_header(Header::new("Access-Control-Allow-Headers", "Content-Type, Authorization, X-CSRF-Token, X-Management-Token"));
             res.set_header(Header::new("Access-Control-Allow-Credentials", "true"));
             res.set_header(Header::new("Access-Control-Max-Age", "86400"));
         }
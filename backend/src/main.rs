@@ -1,5 +1,5 @@
 use backend::{
-    routes::{all_options, cast_ballot, create_vote, get_csrf_token, get_result, get_vote, list_votes, AppState},
+    routes::{all_options, cast_ballot, create_vote, get_csrf_token, get_pairwise, get_analytics, get_ballots, get_ballots_csv, get_ballots_blt, compare_methods, recount, withdraw_option, issue_credentials, end_vote, edit_description, extend_vote, delete_vote, get_result, get_vote, list_votes, AppState},
     cors::CORS,
     catchers::{bad_request, forbidden, internal_error, not_found, too_many_requests},
 };
@@ -14,6 +14,11 @@ use uuid::Uuid;
 static STATIC_DIR: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR/static");
 
 async fn check_pending_votes(pool: &PgPool) -> Result<(), Box<dyn std::error::Error>> {
+    let opened = backend::processor::VoteProcessor::open_scheduled_votes(pool).await?;
+    if opened > 0 {
+        info!("🗳️ Opened {} scheduled votes", opened);
+    }
+
     let votes = sqlx::query!(
         "SELECT id FROM active_votes.votes
          WHERE state = 'active' AND voting_ends_at <= NOW()"
@@ -110,6 +115,19 @@ async fn rocket(
                 create_vote,
                 cast_ballot,
                 get_result,
+                get_pairwise,
+                get_analytics,
+                get_ballots,
+                get_ballots_csv,
+                get_ballots_blt,
+                compare_methods,
+                issue_credentials,
+                recount,
+                withdraw_option,
+                end_vote,
+                edit_description,
+                extend_vote,
+                delete_vote,
                 get_vote,
                 list_votes,
                 all_options,
@@ -1,9 +1,24 @@
-use shared::star_logic::{Ballot, Election};
-use std::collections::HashMap;
+use shared::analytics::{analyze, BallotAnalytics};
+use shared::formats::{BallotFile, BallotFormat};
+use futures::{future, stream, Stream, StreamExt};
+use tracing::error;
+use shared::star_logic::{comparison_methods, Ballot, Election, PairwiseAnalysis, RunoffResult, TabulationOutcome, VotingError};
 use time::{Duration, OffsetDateTime};
 use uuid::Uuid;
-use sqlx::{PgPool, postgres::PgQueryResult};
+use sqlx::PgPool;
 use shared::models::*;
+use shared::star_logic::MAX_SCORE_LIMIT;
+use shared::validation::MAX_DESCRIPTION_LENGTH;
+use ring::{digest, rand::{SecureRandom, SystemRandom}};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+
+const MAX_CREDENTIALS_PER_REQUEST: usize = 200;
+const MAX_CREDENTIAL_WEIGHT: u32 = 1_000;
+const MAX_TOTAL_CREDENTIAL_WEIGHT: i64 = 1_000_000;
+const BALLOT_CHUNK_SIZE: i64 = 10_000;
+const BALLOT_PAGE_SIZE: i64 = 100;
+const MAX_SCHEDULE_AHEAD: Duration = Duration::days(30);
+const MAX_DURATION_MINUTES: i64 = (6 * 24 + 23) * 60 + 59;
 
 #[derive(Debug, Clone, thiserror::Error)]
 pub enum ValidationError {
@@ -21,6 +36,99 @@ pub enum ValidationError {
     DatabaseError(String),
     #[error("Maximum active vote limit ({0}) reached")]
     ActiveVoteLimitExceeded(i64),
+    #[error("Seat count must be between 1 and the number of options")]
+    InvalidSeatCount,
+    #[error("Maximum score must be between 1 and {MAX_SCORE_LIMIT}")]
+    InvalidScoreRange,
+    #[error("Already voted")]
+    AlreadyVoted,
+    #[error("Invalid or already used voter credential")]
+    InvalidCredential,
+    #[error("Credential weights must be between 1 and {MAX_CREDENTIAL_WEIGHT}, at most {MAX_CREDENTIALS_PER_REQUEST} per request")]
+    InvalidCredentialRequest,
+    #[error("A vote's credentials cannot carry more than {MAX_TOTAL_CREDENTIAL_WEIGHT} weight in total")]
+    CredentialWeightLimitExceeded,
+    #[error("Options can only be withdrawn after voting ends")]
+    VoteStillOpen,
+    #[error("Cannot withdraw option: {0}")]
+    InvalidWithdrawal(String),
+    #[error("Invalid management token")]
+    InvalidManagementToken,
+    #[error("Vote has already ended")]
+    VoteAlreadyEnded,
+    #[error("A {0} vote cannot become {1}")]
+    InvalidStateTransition(&'static str, &'static str),
+    #[error("The description can only be edited before the first ballot")]
+    BallotsAlreadyCast,
+    #[error("Description exceeds maximum length of {MAX_DESCRIPTION_LENGTH}")]
+    DescriptionTooLong,
+    #[error("Opening time must be within the next 30 days")]
+    InvalidOpeningTime,
+    #[error("Voting has not opened yet")]
+    VoteNotOpen,
+    #[error("Invalid access code")]
+    InvalidAccessCode,
+    #[error("Vote not found")]
+    VoteNotFound,
+}
+
+/// A vote's columns as stored in either `active_votes.votes` or
+/// `archived_votes.votes`, plus its ballot count.
+struct VoteRow {
+    id: Uuid,
+    title: String,
+    description: Option<String>,
+    options: Vec<String>,
+    voting_ends_at: OffsetDateTime,
+    duration_hours: i32,
+    duration_minutes: i32,
+    user_fingerprint: String,
+    seats: i32,
+    winner_method: String,
+    max_score: i32,
+    weighted: bool,
+    blank_policy: String,
+    withdrawn_options: Vec<String>,
+    results_visibility: String,
+    opens_at: Option<OffsetDateTime>,
+    visibility: String,
+    tiebreak_seed: Option<i64>,
+    ballot_count: i64,
+}
+
+impl From<VoteRow> for Vote {
+    fn from(row: VoteRow) -> Self {
+        let tiebreak_seed = (OffsetDateTime::now_utc() > row.voting_ends_at).then(|| {
+            row.tiebreak_seed.map_or_else(|| VoteProcessor::legacy_tiebreak_seed(row.id), |seed| seed as u64)
+        });
+        Vote {
+            id: row.id,
+            title: row.title,
+            description: row.description.unwrap_or_default(),
+            options: row.options,
+            voting_ends_at: row.voting_ends_at,
+            ballot_count: row.ballot_count,
+            duration_hours: row.duration_hours,
+            duration_minutes: row.duration_minutes,
+            user_fingerprint: row.user_fingerprint,
+            seats: row.seats,
+            winner_method: row.winner_method.parse().unwrap_or_default(),
+            max_score: row.max_score,
+            weighted: row.weighted,
+            blank_policy: row.blank_policy.parse().unwrap_or_default(),
+            withdrawn_options: row.withdrawn_options,
+            results_visibility: row.results_visibility.parse().unwrap_or_default(),
+            opens_at: row.opens_at,
+            visibility: row.visibility.parse().unwrap_or_default(),
+            tiebreak_seed,
+        }
+    }
+}
+
+struct StoredBallot {
+    id: i64,
+    scores: Vec<Option<i32>>,
+    weight: i32,
 }
 
 pub struct VoteProcessor;
@@ -28,7 +136,7 @@ pub struct VoteProcessor;
 impl VoteProcessor {
     pub async fn check_active_vote_limit(pool: &PgPool, limit: i64) -> Result<(), ValidationError> {
         let count = sqlx::query_scalar!(
-            "SELECT COUNT(*) FROM active_votes.votes WHERE state = 'active'"
+            "SELECT COUNT(*) FROM active_votes.votes WHERE state IN ('scheduled', 'active')"
         )
         .fetch_one(pool)
         .await
@@ -41,36 +149,70 @@ impl VoteProcessor {
         Ok(())
     }
 
-    pub fn create_vote(request: &CreateVoteRequest) -> Result<Vote, ValidationError> {
-        if request.duration_hours == 0 && request.duration_minutes == 0 {
+    fn check_duration(duration_hours: i32, duration_minutes: i32) -> Result<(), ValidationError> {
+        if duration_hours == 0 && duration_minutes == 0 {
             return Err(ValidationError::DurationTooShort);
         }
 
-        let days = request.duration_hours / 24;
-        let hours = request.duration_hours % 24;
+        let days = duration_hours / 24;
+        let hours = duration_hours % 24;
         
-        if days > 6 || (days == 6 && (hours > 23 || request.duration_minutes > 59)) {
+        if days > 6 || (days == 6 && (hours > 23 || duration_minutes > 59)) {
             return Err(ValidationError::DurationTooLong);
         }
+        Ok(())
+    }
+
+    pub fn create_vote(request: &CreateVoteRequest) -> Result<Vote, ValidationError> {
+        Self::check_duration(request.duration_hours, request.duration_minutes)?;
+
+        let now = OffsetDateTime::now_utc();
+        if let Some(opens_at) = request.opens_at {
+            if opens_at <= now || opens_at > now + MAX_SCHEDULE_AHEAD {
+                return Err(ValidationError::InvalidOpeningTime);
+            }
+        }
+
+        if request.seats < 1 || request.seats as usize > request.options.len() {
+            return Err(ValidationError::InvalidSeatCount);
+        }
+
+        if !(1..=i32::from(MAX_SCORE_LIMIT)).contains(&request.max_score) {
+            return Err(ValidationError::InvalidScoreRange);
+        }
         
         Ok(Vote {
             id: Uuid::new_v4(),
             title: request.title.clone(),
             description: request.description.clone(),
             options: request.options.clone(),
-            voting_ends_at: OffsetDateTime::now_utc()
+            voting_ends_at: request.opens_at.unwrap_or(now)
                 + Duration::hours(request.duration_hours.into())
                 + Duration::minutes(request.duration_minutes.into()),
             duration_hours: request.duration_hours,
             duration_minutes: request.duration_minutes,
-            ballots: Vec::new(),
+            ballot_count: 0,
             user_fingerprint: request.user_fingerprint.clone(),
+            seats: request.seats,
+            winner_method: request.winner_method,
+            max_score: request.max_score,
+            weighted: request.weighted,
+            blank_policy: request.blank_policy,
+            withdrawn_options: Vec::new(),
+            results_visibility: request.results_visibility,
+            opens_at: request.opens_at,
+            visibility: request.visibility,
+            tiebreak_seed: None,
         })
     }
 
-    pub async fn create_vote_db(pool: &PgPool, vote: &Vote) -> Result<PgQueryResult, ValidationError> {
+    /// Stores a new vote along with its management token and, if private, its
+    /// access code. Only hashes of these are kept, so this is the one chance
+    /// to hand them to the creator. The tiebreak seed is drawn here too and
+    /// stays hidden until voting ends.
+    pub async fn create_vote_db(pool: &PgPool, vote: Vote) -> Result<CreateVoteResponse, ValidationError> {
         let active_count = sqlx::query_scalar!(
-            "SELECT COUNT(*) FROM active_votes.votes WHERE state = 'active'"
+            "SELECT COUNT(*) FROM active_votes.votes WHERE state IN ('scheduled', 'active')"
         )
         .fetch_one(pool)
         .await
@@ -81,10 +223,18 @@ impl VoteProcessor {
             return Err(ValidationError::ActiveVoteLimitExceeded(30));
         }
     
+        let rng = SystemRandom::new();
+        let management_token = Self::generate_token(&rng)?;
+        let access_code = match vote.visibility {
+            VoteVisibility::Private => Some(Self::generate_access_code(&rng)?),
+            VoteVisibility::Public | VoteVisibility::Unlisted => None,
+        };
+        let tiebreak_seed = Self::generate_seed(&rng)?;
+        let state = if vote.opens_at.is_some() { VoteState::Scheduled } else { VoteState::Active };
         sqlx::query!(
             "INSERT INTO active_votes.votes 
-             (id, title, description, options, voting_ends_at, duration_hours, duration_minutes, user_fingerprint, state) 
-             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, 'active')",
+             (id, title, description, options, voting_ends_at, duration_hours, duration_minutes, user_fingerprint, seats, winner_method, max_score, weighted, blank_policy, results_visibility, management_token_hash, opens_at, state, visibility, access_code_hash, tiebreak_seed) 
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20)",
             vote.id,
             vote.title,
             vote.description,
@@ -93,6 +243,18 @@ impl VoteProcessor {
             vote.duration_hours,
             vote.duration_minutes,
             vote.user_fingerprint,
+            vote.seats,
+            vote.winner_method.as_str(),
+            vote.max_score,
+            vote.weighted,
+            vote.blank_policy.as_str(),
+            vote.results_visibility.as_str(),
+            Self::hash_credential(&management_token),
+            vote.opens_at,
+            state as VoteState,
+            vote.visibility.as_str(),
+            access_code.as_deref().map(Self::hash_credential),
+            tiebreak_seed,
         )
         .execute(pool)
         .await
@@ -102,273 +264,1004 @@ impl VoteProcessor {
             } else {
                 ValidationError::DatabaseError(e.to_string())
             }
-        )
+        )?;
+        Ok(CreateVoteResponse { vote, management_token, access_code })
     }
 
-    pub async fn get_vote_db(pool: &PgPool, vote_id: Uuid) -> Result<Option<Vote>, ValidationError> {
-        Self::fetch_vote_by_id(pool, vote_id).await
-    }
+    pub async fn cast_ballot_db(
+        pool: &PgPool,
+        vote: &Vote,
+        user_fingerprint: &str,
+        scores: &[Option<i32>],
+        credential: Option<&str>,
+    ) -> Result<BallotResponse, ValidationError> {
+        if !vote.has_opened() {
+            return Err(ValidationError::VoteNotOpen);
+        }
+
+        let mut tx = pool.begin().await
+            .map_err(|e| ValidationError::DatabaseError(e.to_string()))?;
+
+        let (credential_id, weight) = if vote.weighted {
+            let token = credential.ok_or(ValidationError::InvalidCredential)?;
+            let used = sqlx::query!(
+                "UPDATE active_votes.voter_credentials SET used_at = NOW()
+                 WHERE vote_id = $1 AND token_hash = $2 AND used_at IS NULL
+                 RETURNING id, weight",
+                vote.id,
+                Self::hash_credential(token)
+            )
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| ValidationError::DatabaseError(e.to_string()))?
+            .ok_or(ValidationError::InvalidCredential)?;
+            (Some(used.id), used.weight)
+        } else {
+            (None, 1)
+        };
 
-    pub async fn fetch_vote_by_id(pool: &PgPool, vote_id: Uuid) -> Result<Option<Vote>, ValidationError> {
+        // Unweighted ballots are unique per fingerprint; weighted ones only per
+        // credential, which the update above has already spent.
         let record = sqlx::query!(
-            "SELECT id, title, description, options, voting_ends_at, duration_hours, duration_minutes, user_fingerprint 
-             FROM active_votes.votes WHERE id = $1",
+            "INSERT INTO active_votes.ballots (vote_id, user_fingerprint, scores, weight, credential_id) 
+             VALUES ($1, $2, $3, $4, $5) 
+             RETURNING id as ballot_id, cast_at",
+            vote.id,
+            user_fingerprint,
+            scores as _,
+            weight,
+            credential_id
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| 
+            if e.to_string().contains("unique_voter") {
+                ValidationError::AlreadyVoted
+            } else {
+                ValidationError::DatabaseError(e.to_string())
+            }
+        )?;
+
+        tx.commit().await
+            .map_err(|e| ValidationError::DatabaseError(e.to_string()))?;
+
+        Ok(BallotResponse {
+            ballot_id: record.ballot_id,
+            vote_id: vote.id,
+            cast_at: record.cast_at,
+        })
+    }
+
+    pub async fn issue_credentials(
+        pool: &PgPool,
+        vote_id: Uuid,
+        management_token: &str,
+        weights: &[u32],
+    ) -> Result<Vec<VoterCredential>, ValidationError> {
+        Self::authorize(pool, vote_id, management_token).await?;
+        if weights.is_empty()
+            || weights.len() > MAX_CREDENTIALS_PER_REQUEST
+            || weights.iter().any(|&w| !(1..=MAX_CREDENTIAL_WEIGHT).contains(&w)) {
+            return Err(ValidationError::InvalidCredentialRequest);
+        }
+
+        let rng = SystemRandom::new();
+        let mut tx = pool.begin().await
+            .map_err(|e| ValidationError::DatabaseError(e.to_string()))?;
+
+        // Locking the vote serialises concurrent requests against the total.
+        sqlx::query!("SELECT id FROM active_votes.votes WHERE id = $1 FOR UPDATE", vote_id)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| ValidationError::DatabaseError(e.to_string()))?;
+        let issued = sqlx::query_scalar!(
+            "SELECT COALESCE(SUM(weight), 0) FROM active_votes.voter_credentials WHERE vote_id = $1",
             vote_id
         )
-        .fetch_optional(pool)
+        .fetch_one(&mut *tx)
         .await
-        .map_err(|e| ValidationError::DatabaseError(e.to_string()))?;
-    
-        if let Some(vote) = record {
-            let ballots = sqlx::query!(
-                "SELECT scores, user_fingerprint FROM active_votes.ballots WHERE vote_id = $1",
-                vote_id
+        .map_err(|e| ValidationError::DatabaseError(e.to_string()))?
+        .unwrap_or(0);
+        let requested: i64 = weights.iter().map(|&w| i64::from(w)).sum();
+        if issued + requested > MAX_TOTAL_CREDENTIAL_WEIGHT {
+            return Err(ValidationError::CredentialWeightLimitExceeded);
+        }
+        let mut credentials = Vec::with_capacity(weights.len());
+
+        for &weight in weights {
+            let token = Self::generate_token(&rng)?;
+
+            sqlx::query!(
+                "INSERT INTO active_votes.voter_credentials (vote_id, token_hash, weight) VALUES ($1, $2, $3)",
+                vote_id,
+                Self::hash_credential(&token),
+                weight as i32
             )
-            .fetch_all(pool)
+            .execute(&mut *tx)
             .await
             .map_err(|e| ValidationError::DatabaseError(e.to_string()))?;
-    
-            let vote_ballots = ballots.into_iter()
-                .map(|b| VoteBallot { 
-                    scores: vote.options.iter().enumerate()
-                        .map(|(i, opt)| (opt.clone(), b.scores[i] as i8))
-                        .collect(),
-                    csrf_token: String::new(),
-                    captcha_token: String::new(),
-                    user_fingerprint: b.user_fingerprint,
-                })
-                .collect();
-    
-            return Ok(Some(Vote {
-                id: vote.id,
-                title: vote.title,
-                description: vote.description.unwrap_or_default(),
-                options: vote.options,
-                voting_ends_at: vote.voting_ends_at,
-                ballots: vote_ballots,
-                duration_hours: vote.duration_hours,
-                duration_minutes: vote.duration_minutes,
-                user_fingerprint: vote.user_fingerprint,
-            }));
-        }
-
-        let archived = sqlx::query!(
-            "SELECT id, title, description, options, voting_ends_at, duration_hours, duration_minutes, user_fingerprint 
-             FROM archived_votes.votes WHERE id = $1",
+
+            credentials.push(VoterCredential { token, weight });
+        }
+
+        tx.commit().await
+            .map_err(|e| ValidationError::DatabaseError(e.to_string()))?;
+        Ok(credentials)
+    }
+
+    /// Withdraws `option` from a vote whose voting has ended. Ballots stay as
+    /// cast; results are recounted as if the option had never been listed.
+    pub async fn withdraw_option(
+        pool: &PgPool,
+        vote: &Vote,
+        management_token: &str,
+        option: &str,
+    ) -> Result<(), ValidationError> {
+        Self::authorize(pool, vote.id, management_token).await?;
+        if !vote.is_ended() {
+            return Err(ValidationError::VoteStillOpen);
+        }
+        if !vote.options.iter().any(|opt| opt == option) {
+            return Err(ValidationError::InvalidWithdrawal(format!("{option} is not an option")));
+        }
+        if vote.withdrawn_options.iter().any(|opt| opt == option) {
+            return Err(ValidationError::InvalidWithdrawal(format!("{option} is already withdrawn")));
+        }
+        let remaining = vote.options.len() - vote.withdrawn_options.len() - 1;
+        if remaining < (vote.seats as usize).max(2) {
+            return Err(ValidationError::InvalidWithdrawal("too few options would remain".into()));
+        }
+
+        // Not archived yet: archive_vote records the adjusted result.
+        let updated = sqlx::query!(
+            "UPDATE active_votes.votes SET withdrawn_options = array_append(withdrawn_options, $2)
+             WHERE id = $1 AND NOT ($2 = ANY(withdrawn_options))",
+            vote.id,
+            option
+        )
+        .execute(pool)
+        .await
+        .map_err(|e| ValidationError::DatabaseError(e.to_string()))?
+        .rows_affected();
+        if updated > 0 {
+            return Ok(());
+        }
+
+        let mut adjusted_vote = vote.clone();
+        adjusted_vote.withdrawn_options.push(option.to_string());
+        let result = Self::get_results(pool, &adjusted_vote).await.map_err(ValidationError::DatabaseError)?;
+        let head_to_head = Self::to_json(&result.head_to_head)?;
+        let final_stats = Self::to_json(&FinalStats::new(result.stats, result.ranking))?;
+
+        // The adjusted result assumes no other withdrawal landed in the meantime.
+        let updated = sqlx::query!(
+            "UPDATE archived_votes.votes
+             SET withdrawn_options = array_append(withdrawn_options, $2), adjusted_winner = $3, adjusted_winners = $4,
+                 adjusted_head_to_head = $5::jsonb, adjusted_final_stats = $6::jsonb
+             WHERE id = $1 AND NOT ($2 = ANY(withdrawn_options)) AND withdrawn_options = $7",
+            vote.id,
+            option,
+            result.winner,
+            &result.winners,
+            head_to_head,
+            final_stats,
+            &vote.withdrawn_options
+        )
+        .execute(pool)
+        .await
+        .map_err(|e| ValidationError::DatabaseError(e.to_string()))?
+        .rows_affected();
+        if updated == 0 {
+            return Err(ValidationError::InvalidWithdrawal(format!("{option} was withdrawn or the vote changed in the meantime")));
+        }
+
+        Ok(())
+    }
+
+    /// Checks `management_token` against the vote and returns the vote's state.
+    pub async fn authorize(pool: &PgPool, vote_id: Uuid, management_token: &str) -> Result<VoteState, ValidationError> {
+        let token_hash = Self::hash_credential(management_token);
+        let active = sqlx::query!(
+            r#"SELECT state AS "state: VoteState", management_token_hash FROM active_votes.votes WHERE id = $1"#,
             vote_id
         )
         .fetch_optional(pool)
         .await
         .map_err(|e| ValidationError::DatabaseError(e.to_string()))?;
-    
-        if let Some(vote) = archived {
-            let ballots = sqlx::query!(
-                "SELECT scores, user_fingerprint FROM archived_votes.ballots WHERE vote_id = $1",
-                vote_id
-            )
-            .fetch_all(pool)
+        if let Some(vote) = active {
+            return match vote.management_token_hash {
+                Some(hash) if hash == token_hash => Ok(vote.state),
+                _ => Err(ValidationError::InvalidManagementToken),
+            };
+        }
+
+        let archived = sqlx::query_scalar!(
+            "SELECT management_token_hash FROM archived_votes.votes WHERE id = $1",
+            vote_id
+        )
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| ValidationError::DatabaseError(e.to_string()))?;
+        match archived {
+            Some(Some(hash)) if hash == token_hash => Ok(VoteState::Concluded),
+            _ => Err(ValidationError::InvalidManagementToken),
+        }
+    }
+
+    /// Scheduled and active votes can be edited (keeping their state); a
+    /// scheduled vote opens, an active one concludes. A concluded vote stays
+    /// as it is. Any vote can be deleted.
+    pub(crate) fn check_transition(from: VoteState, to: VoteState) -> Result<(), ValidationError> {
+        use VoteState::*;
+        match (from, to) {
+            (Scheduled, Scheduled | Active)
+            | (Active, Active | Concluded) => Ok(()),
+            _ => Err(ValidationError::InvalidStateTransition(from.as_str(), to.as_str())),
+        }
+    }
+
+    /// Opens the scheduled votes whose opening time has passed.
+    pub async fn open_scheduled_votes(pool: &PgPool) -> Result<u64, ValidationError> {
+        sqlx::query!(
+            "UPDATE active_votes.votes SET state = 'active'
+             WHERE state = 'scheduled' AND opens_at <= NOW()"
+        )
+        .execute(pool)
+        .await
+        .map(|result| result.rows_affected())
+        .map_err(|e| ValidationError::DatabaseError(e.to_string()))
+    }
+
+    /// Replaces the description of a vote nobody has voted in yet.
+    pub async fn edit_description(
+        pool: &PgPool,
+        vote: &Vote,
+        management_token: &str,
+        description: &str,
+    ) -> Result<(), ValidationError> {
+        let state = Self::authorize(pool, vote.id, management_token).await?;
+        Self::check_transition(state, state)?;
+        if vote.is_ended() {
+            return Err(ValidationError::VoteAlreadyEnded);
+        }
+        if description.len() > MAX_DESCRIPTION_LENGTH {
+            return Err(ValidationError::DescriptionTooLong);
+        }
+
+        let updated = sqlx::query!(
+            "UPDATE active_votes.votes SET description = $2
+             WHERE id = $1 AND state IN ('scheduled', 'active')
+             AND NOT EXISTS (SELECT 1 FROM active_votes.ballots WHERE vote_id = $1)",
+            vote.id,
+            description
+        )
+        .execute(pool)
+        .await
+        .map_err(|e| ValidationError::DatabaseError(e.to_string()))?
+        .rows_affected();
+        if updated > 0 {
+            return Ok(());
+        }
+
+        // Something changed since `authorize`; find out what.
+        let current = sqlx::query!(
+            r#"SELECT state AS "state: VoteState",
+                      EXISTS (SELECT 1 FROM active_votes.ballots WHERE vote_id = $1) AS "has_ballots!"
+               FROM active_votes.votes WHERE id = $1"#,
+            vote.id
+        )
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| ValidationError::DatabaseError(e.to_string()))?;
+        Err(match current {
+            None => ValidationError::VoteNotFound,
+            Some(current) if current.has_ballots => ValidationError::BallotsAlreadyCast,
+            Some(_) => ValidationError::VoteAlreadyEnded,
+        })
+    }
+
+    /// Moves the deadline of a vote that hasn't ended back; the total duration stays
+    /// within the limits `create_vote` applies.
+    pub async fn extend_vote(
+        pool: &PgPool,
+        vote: &Vote,
+        management_token: &str,
+        hours: i32,
+        minutes: i32,
+    ) -> Result<(), ValidationError> {
+        let state = Self::authorize(pool, vote.id, management_token).await?;
+        Self::check_transition(state, state)?;
+        if vote.is_ended() {
+            return Err(ValidationError::VoteAlreadyEnded);
+        }
+        let (duration_hours, duration_minutes) = Self::extended_duration(vote, hours, minutes)?;
+
+        let updated = sqlx::query!(
+            "UPDATE active_votes.votes
+             SET voting_ends_at = $2, duration_hours = $3, duration_minutes = $4
+             WHERE id = $1 AND state IN ('scheduled', 'active') AND voting_ends_at > NOW()",
+            vote.id,
+            vote.voting_ends_at + Duration::hours(hours.into()) + Duration::minutes(minutes.into()),
+            duration_hours,
+            duration_minutes
+        )
+        .execute(pool)
+        .await
+        .map_err(|e| ValidationError::DatabaseError(e.to_string()))?
+        .rows_affected();
+        if updated == 0 {
+            return Err(ValidationError::VoteAlreadyEnded);
+        }
+        Ok(())
+    }
+
+    /// The vote's duration once `hours` and `minutes` are added, split back
+    /// into hours and minutes.
+    pub(crate) fn extended_duration(vote: &Vote, hours: i32, minutes: i32) -> Result<(i32, i32), ValidationError> {
+        if hours < 0 || minutes < 0 || (hours == 0 && minutes == 0) {
+            return Err(ValidationError::InvalidDuration);
+        }
+
+        // Widened so no request can overflow it; within the limit it fits back in i32.
+        let total_minutes = (i64::from(vote.duration_hours) + i64::from(hours)) * 60
+            + i64::from(vote.duration_minutes) + i64::from(minutes);
+        if total_minutes > MAX_DURATION_MINUTES {
+            return Err(ValidationError::DurationTooLong);
+        }
+        let total_minutes = total_minutes as i32;
+        let (duration_hours, duration_minutes) = (total_minutes / 60, total_minutes % 60);
+        Self::check_duration(duration_hours, duration_minutes)?;
+        Ok((duration_hours, duration_minutes))
+    }
+
+    /// Ends an open vote now and archives it straight away.
+    pub async fn end_vote(pool: &PgPool, vote: &Vote, management_token: &str) -> Result<(), ValidationError> {
+        let state = Self::authorize(pool, vote.id, management_token).await?;
+        Self::check_transition(state, VoteState::Concluded)?;
+        if vote.is_ended() {
+            return Err(ValidationError::VoteAlreadyEnded);
+        }
+        let mut vote = vote.clone();
+        vote.end_now();
+
+        let updated = sqlx::query!(
+            "UPDATE active_votes.votes SET voting_ends_at = $2
+             WHERE id = $1 AND state = 'active' AND voting_ends_at > $2",
+            vote.id,
+            vote.voting_ends_at
+        )
+        .execute(pool)
+        .await
+        .map_err(|e| ValidationError::DatabaseError(e.to_string()))?
+        .rows_affected();
+        if updated == 0 {
+            return Err(ValidationError::VoteAlreadyEnded);
+        }
+
+        // Voting is closed from here on; if archiving fails, the cleanup task retries it.
+        Self::archive_vote(pool, vote.id).await
+    }
+
+    /// Deletes a vote and its ballots, whether it is still open or archived.
+    pub async fn delete_vote(pool: &PgPool, vote_id: Uuid, management_token: &str) -> Result<(), ValidationError> {
+        Self::authorize(pool, vote_id, management_token).await?;
+        // A concluded vote may not have been archived yet, so try both tables.
+        let deleted = sqlx::query!("DELETE FROM active_votes.votes WHERE id = $1", vote_id)
+            .execute(pool)
             .await
-            .map_err(|e| ValidationError::DatabaseError(e.to_string()))?;
-    
-            let vote_ballots = ballots.into_iter()
-                .map(|b| VoteBallot { 
-                    scores: vote.options.iter().enumerate()
-                        .map(|(i, opt)| (opt.clone(), b.scores[i] as i8))
-                        .collect(),
-                    csrf_token: String::new(),
-                    captcha_token: String::new(),
-                    user_fingerprint: b.user_fingerprint,
-                })
-                .collect();
-    
-            return Ok(Some(Vote {
-                id: vote.id,
-                title: vote.title,
-                description: vote.description.unwrap_or_default(),
-                options: vote.options,
-                voting_ends_at: vote.voting_ends_at,
-                ballots: vote_ballots,
-                duration_hours: vote.duration_hours,
-                duration_minutes: vote.duration_minutes,
-                user_fingerprint: vote.user_fingerprint,
-            }));
+            .map_err(|e| ValidationError::DatabaseError(e.to_string()))?
+            .rows_affected();
+        if deleted == 0 {
+            sqlx::query!("DELETE FROM archived_votes.votes WHERE id = $1", vote_id)
+                .execute(pool)
+                .await
+                .map_err(|e| ValidationError::DatabaseError(e.to_string()))?;
         }
-    
-        Ok(None)
+        Ok(())
     }
 
-    pub fn get_results(vote: &Vote) -> Result<VoteResult, String> {
+    /// Only private votes need an access code; anyone may open the others.
+    pub async fn check_access_code(pool: &PgPool, vote: &Vote, access_code: Option<&str>) -> Result<(), ValidationError> {
+        if vote.visibility != VoteVisibility::Private {
+            return Ok(());
+        }
+        let Some(access_code) = access_code else { return Err(ValidationError::InvalidAccessCode) };
+        let hash = sqlx::query_scalar!(
+            r#"SELECT access_code_hash AS "hash" FROM active_votes.votes WHERE id = $1
+               UNION ALL
+               SELECT access_code_hash FROM archived_votes.votes WHERE id = $1"#,
+            vote.id
+        )
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| ValidationError::DatabaseError(e.to_string()))?
+        .flatten();
+        Self::check_access_code_hash(hash.as_deref(), access_code)
+    }
+
+    /// Checks `access_code` against the hash stored for a private vote.
+    pub(crate) fn check_access_code_hash(hash: Option<&str>, access_code: &str) -> Result<(), ValidationError> {
+        match hash {
+            Some(hash) if hash == Self::hash_credential(access_code) => Ok(()),
+            _ => Err(ValidationError::InvalidAccessCode),
+        }
+    }
+
+    fn generate_token(rng: &SystemRandom) -> Result<String, ValidationError> {
+        Self::random_string::<32>(rng)
+    }
+
+    /// Short enough to read out or type in: 12 characters.
+    fn generate_access_code(rng: &SystemRandom) -> Result<String, ValidationError> {
+        Self::random_string::<9>(rng)
+    }
+
+    fn generate_seed(rng: &SystemRandom) -> Result<i64, ValidationError> {
+        let mut bytes = [0u8; 8];
+        rng.fill(&mut bytes)
+            .map_err(|_| ValidationError::DatabaseError("Failed to generate tiebreak seed".into()))?;
+        Ok(i64::from_le_bytes(bytes))
+    }
+
+    fn random_string<const N: usize>(rng: &SystemRandom) -> Result<String, ValidationError> {
+        let mut bytes = [0u8; N];
+        rng.fill(&mut bytes)
+            .map_err(|_| ValidationError::DatabaseError("Failed to generate token".into()))?;
+        Ok(URL_SAFE_NO_PAD.encode(bytes))
+    }
+
+    fn to_json<T: serde::Serialize>(value: &T) -> Result<serde_json::Value, ValidationError> {
+        serde_json::to_value(value).map_err(|e| ValidationError::DatabaseError(e.to_string()))
+    }
+
+    pub(crate) fn hash_credential(token: &str) -> String {
+        URL_SAFE_NO_PAD.encode(digest::digest(&digest::SHA256, token.as_bytes()))
+    }
+
+    /// Loads a vote's settings and ballot count without its ballots; tabulation
+    /// streams those separately through `load_election`.
+    pub async fn get_vote_db(pool: &PgPool, vote_id: Uuid) -> Result<Option<Vote>, ValidationError> {
+        let record = sqlx::query_as!(
+            VoteRow,
+            r#"SELECT id AS "id!", title AS "title!", description, options AS "options!", voting_ends_at AS "voting_ends_at!",
+                      duration_hours AS "duration_hours!", duration_minutes AS "duration_minutes!", user_fingerprint AS "user_fingerprint!",
+                      seats AS "seats!", winner_method AS "winner_method!", max_score AS "max_score!", weighted AS "weighted!",
+                      blank_policy AS "blank_policy!", withdrawn_options AS "withdrawn_options!", results_visibility AS "results_visibility!",
+                      opens_at, visibility AS "visibility!", tiebreak_seed, ballot_count AS "ballot_count!"
+               FROM (
+                 SELECT id, title, description, options, voting_ends_at, duration_hours, duration_minutes, user_fingerprint, seats, winner_method, max_score, weighted, blank_policy, withdrawn_options, results_visibility, opens_at, visibility, tiebreak_seed,
+                        (SELECT COUNT(*) FROM active_votes.ballots b WHERE b.vote_id = v.id) AS ballot_count
+                 FROM active_votes.votes v WHERE id = $1
+                 UNION ALL
+                 SELECT id, title, description, options, voting_ends_at, duration_hours, duration_minutes, user_fingerprint, seats, winner_method, max_score, weighted, blank_policy, withdrawn_options, results_visibility, opens_at, visibility, tiebreak_seed,
+                        (SELECT COUNT(*) FROM archived_votes.ballots b WHERE b.vote_id = v.id) AS ballot_count
+                 FROM archived_votes.votes v WHERE id = $1
+               ) v"#,
+            vote_id
+        )
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| ValidationError::DatabaseError(e.to_string()))?;
+
+        Ok(record.map(Vote::from))
+    }
+
+    fn ensure_ended(vote: &Vote) -> Result<(), String> {
         if OffsetDateTime::now_utc() <= vote.voting_ends_at {
             return Err("Vote is still ongoing".into());
         }
-    
-        let mut election = Election::new();
+        Ok(())
+    }
+
+    /// Without the seed, which is withheld until voting ends, a tie that only a
+    /// random lot would settle is left unresolved.
+    fn empty_election(vote: &Vote) -> Result<Election<String>, String> {
+        let max_score = i8::try_from(vote.max_score).map_err(|_| "Invalid score range".to_string())?;
+        let mut election = vote.tiebreak_seed.map_or_else(Election::new, Election::with_tiebreak_seed)
+            .with_max_score(max_score)
+            .map_err(|e| e.to_string())?
+            .with_blank_policy(vote.blank_policy);
         for option in &vote.options {
             if let Err(e) = election.add_option(option.clone()) {
                 return Err(format!("Failed to add option: {}", e));
             }
         }
-    
-        for ballot in &vote.ballots {
-            if let Err(e) = election.cast_ballot(Ballot::new(ballot.scores.clone()).unwrap()) {
-                return Err(e.to_string());
+        Ok(election)
+    }
+
+    async fn load_election(pool: &PgPool, vote: &Vote) -> Result<Election<String>, String> {
+        Self::ensure_ended(vote)?;
+        Self::tally(pool, vote).await
+    }
+
+    /// Tallies a vote's ballots straight from the database, `BALLOT_CHUNK_SIZE`
+    /// rows at a time, so memory use stays flat however many ballots were cast.
+    async fn tally(pool: &PgPool, vote: &Vote) -> Result<Election<String>, String> {
+        let mut election = Self::empty_election(vote)?;
+        let max_score = election.max_score();
+        Self::for_each_ballot(pool, vote, max_score, |ballot| {
+            election.cast_ballot(ballot).map_err(|e| e.to_string())
+        })
+        .await?;
+        Ok(election)
+    }
+
+    /// The ballots of a concluded vote in the order they were cast, written as
+    /// `format` a chunk at a time rather than gathered first.
+    pub fn export_ballots(pool: &PgPool, vote: &Vote, format: BallotFormat) -> Result<impl Stream<Item = String> + Send + 'static, String> {
+        Self::ensure_ended(vote)?;
+        let max_score = Self::empty_election(vote)?.max_score();
+        let file = BallotFile {
+            title: vote.title.clone(),
+            options: vote.options.clone(),
+            seats: vote.seats as usize,
+            max_score,
+            ballots: Vec::new(),
+        };
+        let header = file.header(format);
+
+        // One item per chunk of ballots, the footer going with the last. A
+        // failed chunk ends the stream early, leaving a BLT file visibly cut off.
+        let rows = stream::unfold(Some((pool.clone(), vote.clone(), file, 0)), move |state| async move {
+            let (pool, vote, file, after) = state?;
+            let chunk = match Self::ballot_chunk(&pool, vote.id, after, BALLOT_CHUNK_SIZE).await {
+                Ok(chunk) => chunk,
+                Err(e) => {
+                    error!(vote_id = %vote.id, "Ballot export stopped: {e}");
+                    return None;
+                }
+            };
+            let is_last_chunk = (chunk.len() as i64) < BALLOT_CHUNK_SIZE;
+            let after = chunk.last().map_or(after, |last| last.id);
+            let mut text = String::new();
+            for row in chunk {
+                match Self::stored_ballot(&vote, max_score, row) {
+                    Ok(ballot) => text.push_str(&file.row(format, &ballot)),
+                    Err(e) => {
+                        error!(vote_id = %vote.id, "Ballot export stopped: {e}");
+                        return None;
+                    }
+                }
+            }
+            if is_last_chunk {
+                text.push_str(&file.footer(format));
+                return Some((text, None));
+            }
+            Some((text, Some((pool, vote, file, after))))
+        });
+        Ok(stream::once(future::ready(header)).chain(rows))
+    }
+
+    /// Up to `BALLOT_PAGE_SIZE` ballots of a concluded vote cast after the
+    /// ballot `after`; start from 0.
+    pub async fn ballot_page(pool: &PgPool, vote: &Vote, after: i64) -> Result<BallotPage, String> {
+        Self::ensure_ended(vote)?;
+        let rows = Self::ballot_chunk(pool, vote.id, after, BALLOT_PAGE_SIZE).await?;
+        let next = match rows.last() {
+            Some(last) if rows.len() as i64 == BALLOT_PAGE_SIZE => Some(last.id),
+            _ => None,
+        };
+        let ballots = rows.into_iter()
+            .map(|row| BallotRow {
+                scores: (0..vote.options.len())
+                    .map(|i| row.scores.get(i).copied().flatten().map(|score| score as i8))
+                    .collect(),
+                weight: row.weight as u32,
+            })
+            .collect();
+        Ok(BallotPage { ballots, next })
+    }
+
+    /// Each half is limited on its own and compares `(vote_id, id)` as a row,
+    /// which only the `(vote_id, id)` index can serve, so a chunk is an index
+    /// range scan rather than a sort of every remaining ballot.
+    async fn ballot_chunk(pool: &PgPool, vote_id: Uuid, after: i64, limit: i64) -> Result<Vec<StoredBallot>, String> {
+        sqlx::query_as!(
+            StoredBallot,
+            r#"SELECT id AS "id!", scores AS "scores!: Vec<Option<i32>>", weight AS "weight!" FROM (
+                 (SELECT id, scores, weight FROM active_votes.ballots
+                  WHERE vote_id = $1 AND (vote_id, id) > ($1, $2) ORDER BY vote_id, id LIMIT $3)
+                 UNION ALL
+                 (SELECT id, scores, weight FROM archived_votes.ballots
+                  WHERE vote_id = $1 AND (vote_id, id) > ($1, $2) ORDER BY vote_id, id LIMIT $3)
+               ) b ORDER BY id LIMIT $3"#,
+            vote_id,
+            after,
+            limit
+        )
+        .fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string())
+    }
+
+    async fn for_each_ballot(
+        pool: &PgPool,
+        vote: &Vote,
+        max_score: i8,
+        mut f: impl FnMut(Ballot<String>) -> Result<(), String>,
+    ) -> Result<(), String> {
+        let mut last_id = 0;
+
+        loop {
+            let chunk = Self::ballot_chunk(pool, vote.id, last_id, BALLOT_CHUNK_SIZE).await?;
+
+            let Some(last) = chunk.last() else { break };
+            last_id = last.id;
+            let is_last_chunk = (chunk.len() as i64) < BALLOT_CHUNK_SIZE;
+
+            for row in chunk {
+                f(Self::stored_ballot(vote, max_score, row)?)?;
+            }
+
+            if is_last_chunk {
+                break;
             }
         }
-    
+        Ok(())
+    }
+
+    fn stored_ballot(vote: &Vote, max_score: i8, row: StoredBallot) -> Result<Ballot<String>, String> {
+        let scores = vote.options.iter().cloned()
+            .zip(row.scores)
+            .filter_map(|(option, score)| score.map(|score| (option, score as i8)));
+        Ballot::with_max_score(scores, max_score)
+            .map(|ballot| ballot.with_weight(row.weight as u32))
+            .map_err(|e| e.to_string())
+    }
+
+    async fn load_adjusted_election(pool: &PgPool, vote: &Vote) -> Result<Election<String>, String> {
+        let election = Self::load_election(pool, vote).await?;
+        if vote.withdrawn_options.is_empty() {
+            return Ok(election);
+        }
+        election.without_options(&vote.withdrawn_options).map_err(|e| e.to_string())
+    }
+
+    pub async fn get_pairwise(pool: &PgPool, vote: &Vote) -> Result<PairwiseAnalysis<String>, String> {
+        Self::load_adjusted_election(pool, vote).await.map(|election| election.pairwise_analysis())
+    }
+
+    pub async fn get_analytics(pool: &PgPool, vote: &Vote) -> Result<BallotAnalytics<String>, String> {
+        Self::load_adjusted_election(pool, vote).await.map(|election| analyze(&election))
+    }
+
+    pub async fn compare_methods(pool: &PgPool, vote: &Vote) -> Result<Vec<MethodComparison>, String> {
+        let election = Self::load_adjusted_election(pool, vote).await?;
+        Ok(comparison_methods(election.max_score())
+            .iter()
+            .map(|method| match method.winner(&election) {
+                Ok(winner) => MethodComparison {
+                    method: method.name().into(),
+                    winner: Some(winner),
+                    error: None,
+                },
+                Err(e) => MethodComparison {
+                    method: method.name().into(),
+                    winner: None,
+                    error: Some(e.to_string()),
+                },
+            })
+            .collect())
+    }
+
+    pub async fn get_results(pool: &PgPool, vote: &Vote) -> Result<VoteResult, String> {
+        let election = Self::load_election(pool, vote).await?;
+        if vote.withdrawn_options.is_empty() {
+            return Self::tabulate(vote, &election);
+        }
+
+        let adjusted = election.without_options(&vote.withdrawn_options).map_err(|e| e.to_string())?;
+        Ok(VoteResult {
+            withdrawn: vote.withdrawn_options.clone(),
+            original: Some(Box::new(Self::tabulate(vote, &election)?)),
+            ..Self::tabulate(vote, &adjusted)?
+        })
+    }
+
+    /// The running tally of a vote that is still open. Options can only be
+    /// withdrawn after voting ends, so there is nothing to adjust for.
+    pub async fn get_provisional_results(pool: &PgPool, vote: &Vote) -> Result<VoteResult, String> {
+        let election = Self::tally(pool, vote).await?;
+        Ok(VoteResult {
+            provisional: !vote.is_ended(),
+            ..Self::tabulate(vote, &election)?
+        })
+    }
+
+    fn tabulate(vote: &Vote, election: &Election<String>) -> Result<VoteResult, String> {
+        if vote.seats > 1 {
+            return Self::get_multi_winner_results(vote, election);
+        }
+
         match election.determine_winner() {
             Ok(result) => {
                 Ok(VoteResult {
-                    winner: Some(result.winner),
-                    error: None,
-                    stats: Self::calculate_stats(vote),
-                    head_to_head: Some(HeadToHeadResult {
-                        finalist1: result.finalist1,
-                        finalist2: result.finalist2,
-                        finalist1_votes: result.head_to_head.0,
-                        finalist2_votes: result.head_to_head.1
-                    }),
+                    winner: Some(result.winner.clone()),
+                    winners: vec![result.winner.clone()],
+                    seat_rounds: Vec::new(),
+                    ranking: election.ranking().map_err(|e| e.to_string())?,
+                    outcome: TabulationOutcome::Winner,
+                    stats: Self::calculate_stats(election),
+                    head_to_head: Some(Self::head_to_head(&result)),
+                    margin: Some(election.margin_of_victory(&result).map_err(|e| e.to_string())?),
+                    withdrawn: Vec::new(),
+                    original: None,
+                    provisional: false,
+                    runner_up_matchups: result.runner_up_matchups,
+                    tiebreak: (!result.tiebreak.is_empty()).then_some(result.tiebreak),
                     duration_hours: Some(i64::from(vote.duration_hours)),
                     duration_minutes: Some(i64::from(vote.duration_minutes)),
                 })
             },
-            Err(e) => Ok(VoteResult {
-                winner: None,
-                error: Some(e.to_string()),
-                stats: Self::calculate_stats(vote),
-                head_to_head: None,
-                duration_hours: Some(i64::from(vote.duration_hours)),
-                duration_minutes: Some(i64::from(vote.duration_minutes)),
-            })
+            Err(e) => Self::failed_result(vote, election, e),
         }
-    }    
+    }
 
-    pub fn calculate_stats(vote: &Vote) -> VoteStats {
-        let mut option_scores: HashMap<String, VoteOptionStats> = vote
-            .options
-            .iter()
-            .map(|option| {
-                let frequency = (0..=5).map(|score| (score as i8, 0)).collect();
+    /// Re-tabulates an archived vote from its ballots and diffs the outcome
+    /// against what was stored when it was archived. `None` if the vote isn't archived.
+    pub async fn recount(pool: &PgPool, vote_id: Uuid) -> Result<Option<RecountReport>, ValidationError> {
+        let Some(stored) = sqlx::query!(
+            "SELECT winner, head_to_head, final_stats, winners, runner_up_matchups, tiebreak,
+                    adjusted_winner, adjusted_winners, adjusted_head_to_head, adjusted_final_stats
+             FROM archived_votes.votes WHERE id = $1",
+            vote_id
+        )
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| ValidationError::DatabaseError(e.to_string()))? else {
+            return Ok(None);
+        };
+
+        let vote = Self::get_vote_db(pool, vote_id).await?
+            .ok_or_else(|| ValidationError::DatabaseError("Vote not found".into()))?;
+        let election = Self::load_election(pool, &vote).await
+            .map_err(ValidationError::DatabaseError)?;
+        let recounted = Self::tabulate(&vote, &election).map_err(ValidationError::DatabaseError)?;
+
+        let mut result = Vec::new();
+        let mut fields = Vec::new();
+        let stored_head_to_head = Self::stored_head_to_head(Some(stored.head_to_head));
+        let stored_runoff = stored.runner_up_matchups.zip(stored.tiebreak).and_then(|(matchups, tiebreak)| {
+            serde_json::from_value(matchups).ok().zip(serde_json::from_value(tiebreak).ok())
+        });
+        match (vote.seats, stored_head_to_head, stored_runoff) {
+            (1, Some(head_to_head), Some((runner_up_matchups, tiebreak))) if recounted.head_to_head.is_some() => {
+                let stored_result = RunoffResult {
+                    winner: stored.winner.clone(),
+                    finalist1: head_to_head.finalist1,
+                    finalist2: head_to_head.finalist2,
+                    head_to_head: (head_to_head.finalist1_votes, head_to_head.finalist2_votes),
+                    runner_up_matchups,
+                    tiebreak,
+                };
+                result = election.verify(&stored_result).map_err(|e| ValidationError::DatabaseError(e.to_string()))?;
+            }
+            // Archived before the runner-up matchups and tiebreak were kept, or
+            // without a runoff at all.
+            (1, head_to_head, _) => {
+                Self::diff_field(&mut fields, "winner", &stored.winner, &recounted.winner.clone().unwrap_or_default());
+                Self::diff_field(&mut fields, "headToHead", &head_to_head, &recounted.head_to_head);
+            }
+            _ => Self::diff_field(&mut fields, "winners", &stored.winners, &recounted.winners),
+        }
+        Self::diff_final_stats(&mut fields, "", stored.final_stats, &recounted);
+
+        if !vote.withdrawn_options.is_empty() {
+            let adjusted = election.without_options(&vote.withdrawn_options)
+                .map_err(|e| ValidationError::DatabaseError(e.to_string()))?;
+            let recounted = Self::tabulate(&vote, &adjusted).map_err(ValidationError::DatabaseError)?;
+            Self::diff_field(&mut fields, "adjusted.winner", &stored.adjusted_winner, &recounted.winner);
+            Self::diff_field(&mut fields, "adjusted.winners", &stored.adjusted_winners.unwrap_or_default(), &recounted.winners);
+            Self::diff_field(
+                &mut fields,
+                "adjusted.headToHead",
+                &Self::stored_head_to_head(stored.adjusted_head_to_head),
+                &recounted.head_to_head,
+            );
+            Self::diff_final_stats(
+                &mut fields,
+                "adjusted.",
+                stored.adjusted_final_stats.unwrap_or_default(),
+                &recounted,
+            );
+        }
+
+        Ok(Some(RecountReport {
+            vote_id,
+            matches: result.is_empty() && fields.is_empty(),
+            result,
+            fields,
+        }))
+    }
+
+    fn stored_head_to_head(stored: Option<serde_json::Value>) -> Option<HeadToHeadResult> {
+        stored.and_then(|stored| serde_json::from_value::<Option<HeadToHeadResult>>(stored).ok().flatten())
+    }
+
+    fn diff_final_stats(fields: &mut Vec<FieldMismatch>, prefix: &str, stored: serde_json::Value, recounted: &VoteResult) {
+        match serde_json::from_value::<FinalStats>(stored) {
+            Ok(final_stats) => {
+                let (stats, ranking) = final_stats.into_parts();
+                Self::diff_stats(fields, prefix, &stats, &recounted.stats);
+                if let Some(ranking) = ranking {
+                    Self::diff_field(fields, &format!("{prefix}ranking"), &ranking, &recounted.ranking);
+                }
+            }
+            Err(e) => fields.push(FieldMismatch {
+                field: format!("{prefix}finalStats"),
+                stored: format!("unreadable: {e}"),
+                recounted: serde_json::to_string(&recounted.stats).unwrap_or_default(),
+            }),
+        }
+    }
+
+    fn diff_stats(fields: &mut Vec<FieldMismatch>, prefix: &str, stored: &VoteStats, recounted: &VoteStats) {
+        Self::diff_field(fields, &format!("{prefix}totalBallots"), &stored.total_ballots, &recounted.total_ballots);
+        let mut options: Vec<_> = stored.option_scores.keys().chain(recounted.option_scores.keys()).collect();
+        options.sort();
+        options.dedup();
+        for option in options {
+            let (Some(stored), Some(recounted)) = (stored.option_scores.get(option), recounted.option_scores.get(option)) else {
+                fields.push(FieldMismatch {
+                    field: format!("{prefix}optionScores.{option}"),
+                    stored: stored.option_scores.contains_key(option).to_string(),
+                    recounted: recounted.option_scores.contains_key(option).to_string(),
+                });
+                continue;
+            };
+            let field = |name: &str| format!("{prefix}optionScores.{option}.{name}");
+            Self::diff_field(fields, &field("totalScore"), &stored.total_score, &recounted.total_score);
+            Self::diff_field(fields, &field("totalVotes"), &stored.total_votes, &recounted.total_votes);
+            Self::diff_field(fields, &field("abstentions"), &stored.abstentions, &recounted.abstentions);
+            let frequency = |stats: &VoteOptionStats| {
+                let mut frequency: Vec<(i8, usize)> = stats.frequency.iter()
+                    .filter(|(_, &n)| n > 0)
+                    .map(|(&score, &n)| (score, n))
+                    .collect();
+                frequency.sort();
+                frequency
+            };
+            Self::diff_field(fields, &field("frequency"), &frequency(stored), &frequency(recounted));
+        }
+    }
+
+    fn diff_field<V: PartialEq + serde::Serialize>(fields: &mut Vec<FieldMismatch>, field: &str, stored: &V, recounted: &V) {
+        if stored != recounted {
+            fields.push(FieldMismatch {
+                field: field.into(),
+                stored: serde_json::to_string(stored).unwrap_or_default(),
+                recounted: serde_json::to_string(recounted).unwrap_or_default(),
+            });
+        }
+    }
+
+    fn get_multi_winner_results(vote: &Vote, election: &Election<String>) -> Result<VoteResult, String> {
+        let result = match election.determine_winners(vote.seats as usize, vote.winner_method) {
+            Ok(result) => result,
+            Err(e) => return Self::failed_result(vote, election, e),
+        };
+
+        // Only Bloc STAR seats go through a runoff; its first round is the single-winner STAR result.
+        let first_runoff = result.rounds.first().and_then(|round| round.runoff.clone());
+        Ok(VoteResult {
+            winner: result.winners.first().cloned(),
+            winners: result.winners,
+            head_to_head: first_runoff.as_ref().map(Self::head_to_head),
+            runner_up_matchups: first_runoff.as_ref()
+                .map(|runoff| runoff.runner_up_matchups.clone())
+                .unwrap_or_default(),
+            tiebreak: first_runoff
+                .map(|runoff| runoff.tiebreak)
+                .filter(|tiebreak| !tiebreak.is_empty()),
+            seat_rounds: result.rounds,
+            ranking: election.ranking().map_err(|e| e.to_string())?,
+            margin: None,
+            withdrawn: Vec::new(),
+            original: None,
+            provisional: false,
+            outcome: TabulationOutcome::Winner,
+            stats: Self::calculate_stats(election),
+            duration_hours: Some(i64::from(vote.duration_hours)),
+            duration_minutes: Some(i64::from(vote.duration_minutes)),
+        })
+    }
+
+    fn head_to_head(result: &RunoffResult<String>) -> HeadToHeadResult {
+        HeadToHeadResult {
+            finalist1: result.finalist1.clone(),
+            finalist2: result.finalist2.clone(),
+            finalist1_votes: result.head_to_head.0,
+            finalist2_votes: result.head_to_head.1,
+        }
+    }
+
+    /// Ties and too few ballots are results in their own right; any other error is a failure.
+    fn failed_result(vote: &Vote, election: &Election<String>, error: VotingError<String>) -> Result<VoteResult, String> {
+        let outcome = TabulationOutcome::try_from(error).map_err(|e| e.to_string())?;
+        Ok(VoteResult {
+            winner: None,
+            winners: Vec::new(),
+            seat_rounds: Vec::new(),
+            ranking: Vec::new(),
+            outcome,
+            stats: Self::calculate_stats(election),
+            head_to_head: None,
+            runner_up_matchups: Vec::new(),
+            tiebreak: None,
+            margin: None,
+            withdrawn: Vec::new(),
+            original: None,
+            provisional: false,
+            duration_hours: Some(i64::from(vote.duration_hours)),
+            duration_minutes: Some(i64::from(vote.duration_minutes)),
+        })
+    }
+
+    /// Votes created before seeds were stored keep drawing lots from their ID,
+    /// so their results and recounts do not change.
+    fn legacy_tiebreak_seed(vote_id: Uuid) -> u64 {
+        let (high, low) = vote_id.as_u64_pair();
+        high ^ low
+    }
+
+    pub fn calculate_stats(election: &Election<String>) -> VoteStats {
+        let option_scores = election
+            .option_metrics()
+            .map(|(option, metrics)| {
+                let frequency = (0..=election.max_score())
+                    .map(|score| (score, metrics.count(score) as usize))
+                    .collect();
+                let total_votes = metrics.votes() as usize;
+                let average_score = if total_votes > 0 {
+                    metrics.total() as f64 / total_votes as f64
+                } else {
+                    0.0
+                };
                 (
                     option.clone(),
                     VoteOptionStats {
-                        total_score: 0,
-                        average_score: 0.0,
+                        total_score: metrics.total(),
+                        average_score,
                         frequency,
-                        total_votes: 0,
+                        total_votes,
+                        abstentions: metrics.abstentions() as usize,
                     },
                 )
             })
             .collect();
 
-        for ballot in &vote.ballots {
-            for (option, &score) in &ballot.scores {
-                if let Some(stats) = option_scores.get_mut(option) {
-                    stats.total_score += i32::from(score);
-                    *stats.frequency.entry(score).or_insert(0) += 1;
-                    stats.total_votes += 1;
-                }
-            }
-        }
-
-        for stats in option_scores.values_mut() {
-            if stats.total_votes > 0 {
-                stats.average_score = stats.total_score as f64 / stats.total_votes as f64;
-            }
-        }
-
         VoteStats {
             option_scores,
-            total_ballots: vote.ballots.len(),
+            total_ballots: election.ballot_count() as usize,
         }
     }
 
     pub async fn fetch_all_votes(pool: &PgPool) -> Result<Vec<Vote>, ValidationError> {
-        let active_records = sqlx::query!(
-            "SELECT id, title, description, options, voting_ends_at, duration_hours, duration_minutes, user_fingerprint 
-             FROM active_votes.votes 
-             WHERE state IN ('active', 'concluded')
-             ORDER BY created_at DESC"
-        )
-        .fetch_all(pool)
-        .await
-        .map_err(|e| ValidationError::DatabaseError(e.to_string()))?;
-    
-        let archived_records = sqlx::query!(
-            "SELECT id, title, description, options, voting_ends_at, duration_hours, duration_minutes, user_fingerprint 
-             FROM archived_votes.votes 
-             ORDER BY archived_at DESC"
+        let records = sqlx::query_as!(
+            VoteRow,
+            r#"SELECT id AS "id!", title AS "title!", description, options AS "options!", voting_ends_at AS "voting_ends_at!",
+                      duration_hours AS "duration_hours!", duration_minutes AS "duration_minutes!", user_fingerprint AS "user_fingerprint!",
+                      seats AS "seats!", winner_method AS "winner_method!", max_score AS "max_score!", weighted AS "weighted!",
+                      blank_policy AS "blank_policy!", withdrawn_options AS "withdrawn_options!", results_visibility AS "results_visibility!",
+                      opens_at, visibility AS "visibility!", tiebreak_seed, ballot_count AS "ballot_count!"
+               FROM (
+                 SELECT id, title, description, options, voting_ends_at, duration_hours, duration_minutes, user_fingerprint, seats, winner_method, max_score, weighted, blank_policy, withdrawn_options, results_visibility, opens_at, visibility, tiebreak_seed,
+                        (SELECT COUNT(*) FROM active_votes.ballots b WHERE b.vote_id = v.id) AS ballot_count, created_at AS listed_at
+                 FROM active_votes.votes v
+                 WHERE state IN ('scheduled', 'active', 'concluded') AND visibility = 'public'
+                 UNION ALL
+                 SELECT id, title, description, options, voting_ends_at, duration_hours, duration_minutes, user_fingerprint, seats, winner_method, max_score, weighted, blank_policy, withdrawn_options, results_visibility, opens_at, visibility, tiebreak_seed,
+                        (SELECT COUNT(*) FROM archived_votes.ballots b WHERE b.vote_id = v.id) AS ballot_count, archived_at AS listed_at
+                 FROM archived_votes.votes v
+                 WHERE visibility = 'public'
+               ) v
+               ORDER BY listed_at DESC"#
         )
         .fetch_all(pool)
         .await
         .map_err(|e| ValidationError::DatabaseError(e.to_string()))?;
-    
-        let mut votes = Vec::with_capacity(active_records.len() + archived_records.len());
 
-        for vote in active_records {
-            let ballots = sqlx::query!(
-                "SELECT scores, user_fingerprint FROM active_votes.ballots WHERE vote_id = $1",
-                vote.id
-            )
-            .fetch_all(pool)
-            .await
-            .map_err(|e| ValidationError::DatabaseError(e.to_string()))?;
-    
-            let vote_ballots = ballots.into_iter()
-                .map(|b| VoteBallot {
-                    scores: vote.options.iter().enumerate()
-                        .map(|(i, opt)| (opt.clone(), b.scores[i] as i8))
-                        .collect(),
-                    csrf_token: String::new(),
-                    captcha_token: String::new(),
-                    user_fingerprint: b.user_fingerprint,
-                })
-                .collect();
-    
-            votes.push(Vote {
-                id: vote.id,
-                title: vote.title,
-                description: vote.description.unwrap_or_default(),
-                options: vote.options,
-                voting_ends_at: vote.voting_ends_at,
-                ballots: vote_ballots,
-                duration_hours: vote.duration_hours,
-                duration_minutes: vote.duration_minutes,
-                user_fingerprint: vote.user_fingerprint,
-            });
-        }
-
-        for vote in archived_records {
-            let ballots = sqlx::query!(
-                "SELECT scores, user_fingerprint FROM archived_votes.ballots WHERE vote_id = $1",
-                vote.id
-            )
-            .fetch_all(pool)
-            .await
-            .map_err(|e| ValidationError::DatabaseError(e.to_string()))?;
-    
-            let vote_ballots = ballots.into_iter()
-                .map(|b| VoteBallot {
-                    scores: vote.options.iter().enumerate()
-                        .map(|(i, opt)| (opt.clone(), b.scores[i] as i8))
-                        .collect(),
-                    csrf_token: String::new(),
-                    captcha_token: String::new(),
-                    user_fingerprint: b.user_fingerprint,
-                })
-                .collect();
-    
-            votes.push(Vote {
-                id: vote.id,
-                title: vote.title,
-                description: vote.description.unwrap_or_default(),
-                options: vote.options,
-                voting_ends_at: vote.voting_ends_at,
-                ballots: vote_ballots,
-                duration_hours: vote.duration_hours,
-                duration_minutes: vote.duration_minutes,
-                user_fingerprint: vote.user_fingerprint,
-            });
-        }
+        let mut votes: Vec<Vote> = records.into_iter().map(Vote::from).collect();
 
         votes.sort_by(|a, b| {
             let now = OffsetDateTime::now_utc();
@@ -386,11 +1279,24 @@ impl VoteProcessor {
     }
 
     pub async fn archive_vote(pool: &PgPool, vote_id: Uuid) -> Result<(), ValidationError> {
-        let vote = Self::fetch_vote_by_id(pool, vote_id).await?
+        let vote = Self::get_vote_db(pool, vote_id).await?
             .ok_or_else(|| ValidationError::DatabaseError("Vote not found".into()))?;
     
-        let result = Self::get_results(&vote).map_err(|e| ValidationError::DatabaseError(e))?;
-        let stats = Self::calculate_stats(&vote);
+        let mut result = Self::get_results(pool, &vote).await.map_err(ValidationError::DatabaseError)?;
+        let (result, adjusted) = match result.original.take() {
+            Some(original) => (*original, Some(result)),
+            None => (result, None),
+        };
+        let final_stats = Self::to_json(&FinalStats::new(result.stats, result.ranking))?;
+        let head_to_head = Self::to_json(&result.head_to_head)?;
+        let runner_up_matchups = Self::to_json(&result.runner_up_matchups)?;
+        let tiebreak = Self::to_json(&result.tiebreak.unwrap_or_default())?;
+        let adjusted_head_to_head = adjusted.as_ref()
+            .map(|adjusted| Self::to_json(&adjusted.head_to_head))
+            .transpose()?;
+        let adjusted_final_stats = adjusted.as_ref()
+            .map(|adjusted| Self::to_json(&FinalStats::new(adjusted.stats.clone(), adjusted.ranking.clone())))
+            .transpose()?;
     
         let mut tx = pool.begin().await
             .map_err(|e| ValidationError::DatabaseError(e.to_string()))?;
@@ -408,26 +1314,39 @@ impl VoteProcessor {
             INSERT INTO archived_votes.votes (
                 id, user_fingerprint, title, description, created_at, voting_ends_at,
                 archived_at, duration_hours, duration_minutes, options, final_stats,
-                winner, head_to_head
+                winner, head_to_head, seats, winner_method, winners, max_score, weighted, blank_policy,
+                withdrawn_options, adjusted_winner, adjusted_winners, adjusted_head_to_head, adjusted_final_stats,
+                results_visibility, management_token_hash, opens_at, visibility, access_code_hash,
+                runner_up_matchups, tiebreak, tiebreak_seed
             )
             SELECT 
                 v.id, v.user_fingerprint, v.title, v.description, v.created_at, v.voting_ends_at,
                 v.archived_at, v.duration_hours, v.duration_minutes, v.options, $2::jsonb,
-                $3, $4::jsonb
+                $3, $4::jsonb, v.seats, v.winner_method, $5, v.max_score, v.weighted, v.blank_policy,
+                v.withdrawn_options, $6, $7, $8::jsonb, $9::jsonb,
+                v.results_visibility, v.management_token_hash, v.opens_at, v.visibility, v.access_code_hash,
+                $10::jsonb, $11::jsonb, v.tiebreak_seed
             FROM active_votes.votes v WHERE v.id = $1
             "#,
             vote_id,
-            serde_json::to_value(&stats).unwrap(),
+            final_stats,
             result.winner.unwrap_or_default(),
-            serde_json::to_value(&result.head_to_head).unwrap()
+            head_to_head,
+            &result.winners,
+            adjusted.as_ref().and_then(|adjusted| adjusted.winner.clone()),
+            adjusted.as_ref().map(|adjusted| adjusted.winners.as_slice()),
+            adjusted_head_to_head,
+            adjusted_final_stats,
+            runner_up_matchups,
+            tiebreak
         )
         .execute(&mut *tx)
         .await
         .map_err(|e| ValidationError::DatabaseError(e.to_string()))?;
     
         sqlx::query!(
-            "INSERT INTO archived_votes.ballots (id, vote_id, user_fingerprint, scores, cast_at)
-             SELECT id, vote_id, user_fingerprint, scores, cast_at
+            "INSERT INTO archived_votes.ballots (id, vote_id, user_fingerprint, scores, cast_at, weight)
+             SELECT id, vote_id, user_fingerprint, scores, cast_at, weight
              FROM active_votes.ballots WHERE vote_id = $1",
             vote_id
         )
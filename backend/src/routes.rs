@@ -1,4 +1,7 @@
-use rocket::{State, get, post, http::Status, serde::json::Json};
+use rocket::{State, Request, get, post, delete, http::{ContentType, Status}, serde::json::Json};
+use rocket::request::{FromRequest, Outcome};
+use rocket::response::stream::TextStream;
+use futures::stream::{BoxStream, StreamExt};
 use tracing::{error, debug, instrument};
 use std::sync::Mutex;
 use std::collections::HashSet;
@@ -7,7 +10,7 @@ use base64::engine::general_purpose::URL_SAFE_NO_PAD;
 use base64::Engine;
 use rustrict::CensorStr;
 use sqlx::PgPool;
-use shared::{models::*, user_info::UserInfo};
+use shared::{models::*, formats::BallotFormat, user_info::UserInfo, star_logic::PairwiseAnalysis, analytics::BallotAnalytics, validation::validate_ballot};
 use crate::{
    processor::{VoteProcessor, ValidationError},
    utils::parse_vote_id,
@@ -66,6 +69,20 @@ impl CsrfGuard {
     }
 }
 
+/// The management token from the `X-Management-Token` header, for read-only
+/// owner requests that have no JSON body to carry it. Keeping it out of the
+/// query string keeps it out of request logs, browser history and referrers.
+pub struct ManagementToken(Option<String>);
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for ManagementToken {
+    type Error = ();
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        Outcome::Success(ManagementToken(req.headers().get_one("X-Management-Token").map(str::to_string)))
+    }
+}
+
 pub struct AppState {
     pub vote_limiter: RateLimiter,
     pub ballot_limiter: RateLimiter,
@@ -145,7 +162,7 @@ pub async fn create_vote(
     state: &State<AppState>,
     request: Json<CreateVoteRequest>,
     user_info: UserInfo,
-) -> Result<Json<Vote>, (Status, Json<ErrorResponse>)> {
+) -> Result<Json<CreateVoteResponse>, (Status, Json<ErrorResponse>)> {
     let mut request_data = request.into_inner();
     
     debug!("Validating CSRF token for vote creation: length={}", request_data.csrf_token.len());
@@ -201,8 +218,8 @@ pub async fn create_vote(
         return Err((Status::TooManyRequests, Json(e)));
     }
 
-    match VoteProcessor::create_vote_db(&state.db, &vote).await {
-        Ok(_) => Ok(Json(vote)),
+    match VoteProcessor::create_vote_db(&state.db, vote).await {
+        Ok(response) => Ok(Json(response)),
         Err(e) => match e {
             ValidationError::ActiveVoteLimitExceeded(limit) =>
                 Err((Status::BadRequest, Json(ErrorResponse {
@@ -215,11 +232,12 @@ pub async fn create_vote(
     }
 }
 
-#[instrument(skip(state, ballot), fields(vote_id = %id))]
-#[post("/vote/<id>/ballot", format = "json", data = "<ballot>")]
+#[instrument(skip(state, ballot, access_code), fields(vote_id = %id))]
+#[post("/vote/<id>/ballot?<access_code>", format = "json", data = "<ballot>")]
 pub async fn cast_ballot(
     state: &State<AppState>,
     id: &str,
+    access_code: Option<&str>,
     ballot: Json<VoteBallot>,
     user_info: UserInfo
 ) -> Result<Json<BallotResponse>, (Status, Json<ErrorResponse>)> {
@@ -271,44 +289,303 @@ pub async fn cast_ballot(
             Json(ErrorResponse { error: "Vote not found".into() })
         ))?;
 
+    if let Err(status) = check_access(state, &vote, access_code).await {
+        return Err((status, Json(ErrorResponse { error: "Invalid access code".into() })));
+    }
+
+    if let Err(e) = validate_ballot(&ballot_data, &vote.options, vote.max_score) {
+        return Err((Status::BadRequest, Json(ErrorResponse { error: e.to_string() })));
+    }
+
     let scores: Vec<_> = vote.options.iter()
         .map(|opt| ballot_data.scores.get(opt)
-            .map(|&score| score as i32)
-            .unwrap_or(0))
+            .copied()
+            .flatten()
+            .map(i32::from))
         .collect();
 
-    let result = sqlx::query!(
-        "INSERT INTO active_votes.ballots (vote_id, user_fingerprint, scores) 
-         VALUES ($1, $2, $3) 
-         RETURNING id as ballot_id, cast_at",
-        uuid,
-        user_info.user_fingerprint,
-        &scores
+    VoteProcessor::cast_ballot_db(
+        &state.db,
+        &vote,
+        &user_info.user_fingerprint,
+        &scores,
+        ballot_data.credential.as_deref(),
     )
-    .fetch_one(&state.db)
     .await
+    .map(Json)
     .map_err(|e| {
-        let (status, msg) = match e.to_string().contains("unique_voter") {
-            true => (Status::Forbidden, "Already voted"),
-            false => (Status::InternalServerError, "Database error"),
+        let (status, msg) = match e {
+            ValidationError::AlreadyVoted | ValidationError::InvalidCredential | ValidationError::VoteNotOpen =>
+                (Status::Forbidden, e.to_string()),
+            _ => (Status::InternalServerError, "Database error".to_string()),
         };
-        (status, Json(ErrorResponse { error: msg.into() }))
-    })?;
-
-    Ok(Json(BallotResponse {
-        ballot_id: result.ballot_id,
-        vote_id: uuid,
-        cast_at: result.cast_at,
-    }))
+        (status, Json(ErrorResponse { error: msg }))
+    })
+}
+
+#[instrument(skip(state, request), fields(vote_id = %id))]
+#[post("/vote/<id>/credentials", format = "json", data = "<request>")]
+pub async fn issue_credentials(
+    state: &State<AppState>,
+    id: &str,
+    request: Json<IssueCredentialsRequest>,
+) -> Result<Json<Vec<VoterCredential>>, (Status, Json<ErrorResponse>)> {
+    let request_data = request.into_inner();
+    let vote = managed_vote(state, id, &request_data.csrf_token).await?;
+
+    if !vote.weighted || vote.is_ended() {
+        return Err((Status::BadRequest, Json(ErrorResponse { error: "Vote is not accepting weighted credentials".into() })));
+    }
+
+    VoteProcessor::issue_credentials(&state.db, vote.id, &request_data.management_token, &request_data.weights)
+        .await
+        .map(Json)
+        .map_err(|e| match e {
+            ValidationError::InvalidManagementToken =>
+                (Status::Forbidden, Json(ErrorResponse { error: e.to_string() })),
+            ValidationError::InvalidCredentialRequest | ValidationError::CredentialWeightLimitExceeded =>
+                (Status::BadRequest, Json(ErrorResponse { error: e.to_string() })),
+            _ => (Status::InternalServerError, Json(ErrorResponse { error: "Failed to issue credentials".into() })),
+        })
+}
+
+#[instrument(skip(state, request), fields(vote_id = %id))]
+#[post("/vote/<id>/withdraw", format = "json", data = "<request>")]
+pub async fn withdraw_option(
+    state: &State<AppState>,
+    id: &str,
+    request: Json<WithdrawOptionRequest>,
+) -> Result<Status, (Status, Json<ErrorResponse>)> {
+    let request_data = request.into_inner();
+    let vote = managed_vote(state, id, &request_data.csrf_token).await?;
+
+    VoteProcessor::withdraw_option(&state.db, &vote, &request_data.management_token, &request_data.option)
+        .await
+        .map(|_| Status::NoContent)
+        .map_err(|e| match e {
+            ValidationError::InvalidManagementToken =>
+                (Status::Forbidden, Json(ErrorResponse { error: e.to_string() })),
+            ValidationError::VoteStillOpen | ValidationError::InvalidWithdrawal(_) =>
+                (Status::BadRequest, Json(ErrorResponse { error: e.to_string() })),
+            _ => (Status::InternalServerError, Json(ErrorResponse { error: "Failed to withdraw option".into() })),
+        })
+}
+
+/// Checks the CSRF token and loads the vote an owner endpoint acts on.
+async fn managed_vote(state: &AppState, id: &str, csrf_token: &str) -> Result<Vote, (Status, Json<ErrorResponse>)> {
+    let uuid = parse_vote_id(id).map_err(|_| (
+        Status::BadRequest,
+        Json(ErrorResponse { error: "Invalid vote ID".into() })
+    ))?;
+
+    if state.csrf.verify_token(csrf_token).is_err() {
+        return Err((Status::Forbidden, Json(ErrorResponse { error: "Invalid CSRF token".into() })));
+    }
+
+    VoteProcessor::get_vote_db(&state.db, uuid)
+        .await
+        .map_err(|_| (
+            Status::InternalServerError,
+            Json(ErrorResponse { error: "Failed to retrieve vote".into() })
+        ))?
+        .ok_or_else(|| (
+            Status::NotFound,
+            Json(ErrorResponse { error: "Vote not found".into() })
+        ))
+}
+
+/// Private votes are only shown to those who pass their access code.
+async fn check_access(state: &AppState, vote: &Vote, access_code: Option<&str>) -> Result<(), Status> {
+    VoteProcessor::check_access_code(&state.db, vote, access_code)
+        .await
+        .map_err(|e| match e {
+            ValidationError::InvalidAccessCode => Status::Forbidden,
+            _ => Status::InternalServerError,
+        })
+}
+
+/// Loads a vote whose ballot-level data is asked for. That data is only
+/// published once voting ends, so an open vote is `Forbidden`.
+async fn concluded_vote(state: &AppState, id: &str, access_code: Option<&str>) -> Result<Vote, Status> {
+    let uuid = parse_vote_id(id).map_err(|_| Status::BadRequest)?;
+    let vote = VoteProcessor::get_vote_db(&state.db, uuid)
+        .await
+        .map_err(|_| Status::InternalServerError)?
+        .ok_or(Status::NotFound)?;
+    check_access(state, &vote, access_code).await?;
+    if !vote.is_ended() {
+        return Err(Status::Forbidden);
+    }
+    Ok(vote)
+}
+
+fn management_error(e: ValidationError, failure: &str) -> (Status, Json<ErrorResponse>) {
+    let status = match e {
+        ValidationError::InvalidManagementToken => Status::Forbidden,
+        ValidationError::VoteNotFound => Status::NotFound,
+        ValidationError::VoteAlreadyEnded
+        | ValidationError::InvalidStateTransition(..)
+        | ValidationError::BallotsAlreadyCast
+        | ValidationError::DescriptionTooLong
+        | ValidationError::InvalidDuration
+        | ValidationError::DurationTooShort
+        | ValidationError::DurationTooLong => Status::BadRequest,
+        _ => return (Status::InternalServerError, Json(ErrorResponse { error: failure.into() })),
+    };
+    (status, Json(ErrorResponse { error: e.to_string() }))
+}
+
+#[instrument(skip(state, request), fields(vote_id = %id))]
+#[post("/vote/<id>/description", format = "json", data = "<request>")]
+pub async fn edit_description(
+    state: &State<AppState>,
+    id: &str,
+    request: Json<EditDescriptionRequest>,
+) -> Result<Status, (Status, Json<ErrorResponse>)> {
+    let request_data = request.into_inner();
+    let vote = managed_vote(state, id, &request_data.csrf_token).await?;
+
+    if request_data.description.is_inappropriate() {
+        return Err((Status::BadRequest, Json(ErrorResponse {
+            error: format!("Possible profanity detected in description: {}", request_data.description)
+        })));
+    }
+
+    VoteProcessor::edit_description(&state.db, &vote, &request_data.management_token, &request_data.description)
+        .await
+        .map(|_| Status::NoContent)
+        .map_err(|e| management_error(e, "Failed to edit description"))
+}
+
+#[instrument(skip(state, request), fields(vote_id = %id))]
+#[post("/vote/<id>/extend", format = "json", data = "<request>")]
+pub async fn extend_vote(
+    state: &State<AppState>,
+    id: &str,
+    request: Json<ExtendVoteRequest>,
+) -> Result<Status, (Status, Json<ErrorResponse>)> {
+    let request_data = request.into_inner();
+    let vote = managed_vote(state, id, &request_data.csrf_token).await?;
+
+    VoteProcessor::extend_vote(&state.db, &vote, &request_data.management_token, request_data.hours, request_data.minutes)
+        .await
+        .map(|_| Status::NoContent)
+        .map_err(|e| management_error(e, "Failed to extend vote"))
+}
+
+#[instrument(skip(state, request), fields(vote_id = %id))]
+#[post("/vote/<id>/end", format = "json", data = "<request>")]
+pub async fn end_vote(
+    state: &State<AppState>,
+    id: &str,
+    request: Json<ManageVoteRequest>,
+) -> Result<Status, (Status, Json<ErrorResponse>)> {
+    let request_data = request.into_inner();
+    let vote = managed_vote(state, id, &request_data.csrf_token).await?;
+
+    VoteProcessor::end_vote(&state.db, &vote, &request_data.management_token)
+        .await
+        .map(|_| Status::NoContent)
+        .map_err(|e| management_error(e, "Failed to end vote"))
+}
+
+#[instrument(skip(state, request), fields(vote_id = %id))]
+#[delete("/vote/<id>", format = "json", data = "<request>")]
+pub async fn delete_vote(
+    state: &State<AppState>,
+    id: &str,
+    request: Json<ManageVoteRequest>,
+) -> Result<Status, (Status, Json<ErrorResponse>)> {
+    let request_data = request.into_inner();
+    let vote = managed_vote(state, id, &request_data.csrf_token).await?;
+
+    VoteProcessor::delete_vote(&state.db, vote.id, &request_data.management_token)
+        .await
+        .map(|_| Status::NoContent)
+        .map_err(|e| management_error(e, "Failed to delete vote"))
 }
 
-#[instrument(skip(state), fields(vote_id = %id), err)]
-#[get("/vote/<id>/result")]
-pub async fn get_result(state: &State<AppState>, id: &str) -> Result<Json<VoteResult>, Status> {
+#[instrument(skip(state, access_code), fields(vote_id = %id))]
+#[post("/vote/<id>/recount?<access_code>")]
+pub async fn recount(
+    state: &State<AppState>,
+    id: &str,
+    access_code: Option<&str>,
+    user_info: UserInfo,
+) -> Result<Json<RecountReport>, (Status, Json<ErrorResponse>)> {
+    let uuid = parse_vote_id(id).map_err(|_| (
+        Status::BadRequest,
+        Json(ErrorResponse { error: "Invalid vote ID".into() })
+    ))?;
+
+    let rate_limit_key = format!("recount:{}:{}", user_info.user_fingerprint, id);
+    if let Err(e) = state.ballot_limiter.check_rate_limit(&rate_limit_key) {
+        return Err((Status::TooManyRequests, Json(e)));
+    }
+
+    let vote = VoteProcessor::get_vote_db(&state.db, uuid)
+        .await
+        .map_err(|_| (
+            Status::InternalServerError,
+            Json(ErrorResponse { error: "Failed to retrieve vote".into() })
+        ))?
+        .ok_or_else(|| (
+            Status::NotFound,
+            Json(ErrorResponse { error: "Vote not found".into() })
+        ))?;
+    if let Err(status) = check_access(state, &vote, access_code).await {
+        return Err((status, Json(ErrorResponse { error: "Invalid access code".into() })));
+    }
+
+    VoteProcessor::recount(&state.db, uuid)
+        .await
+        .map_err(|_| (
+            Status::InternalServerError,
+            Json(ErrorResponse { error: "Recount failed".into() })
+        ))?
+        .map(Json)
+        .ok_or_else(|| (
+            Status::NotFound,
+            Json(ErrorResponse { error: "Only archived votes can be recounted".into() })
+        ))
+}
+
+/// Before voting ends, the vote's `results_visibility` decides whether a
+/// provisional result is shown, and to whom. The creator proves who they are
+/// with the vote's management token, sent as a header.
+#[instrument(skip(state, access_code, management_token), fields(vote_id = %id), err)]
+#[get("/vote/<id>/result?<access_code>")]
+pub async fn get_result(
+    state: &State<AppState>,
+    id: &str,
+    access_code: Option<&str>,
+    management_token: ManagementToken,
+) -> Result<Json<VoteResult>, Status> {
     let uuid = parse_vote_id(id).map_err(|_| Status::BadRequest)?;
     
     if let Some(vote) = VoteProcessor::get_vote_db(&state.db, uuid).await.map_err(|_| Status::InternalServerError)? {
-        VoteProcessor::get_results(&vote)
+        check_access(state, &vote, access_code).await?;
+        if vote.is_ended() {
+            return VoteProcessor::get_results(&state.db, &vote)
+                .await
+                .map(Json)
+                .map_err(|_| Status::InternalServerError);
+        }
+
+        // Only worth checking the token when it could change the answer.
+        let is_creator = match (vote.results_visibility, management_token.0) {
+            (ResultsVisibility::CreatorOnly, Some(token)) => match VoteProcessor::authorize(&state.db, vote.id, &token).await {
+                Ok(_) => true,
+                Err(ValidationError::InvalidManagementToken) => false,
+                Err(_) => return Err(Status::InternalServerError),
+            },
+            _ => false,
+        };
+        if !vote.results_visibility.shows_provisional(is_creator) {
+            return Err(Status::Forbidden);
+        }
+        VoteProcessor::get_provisional_results(&state.db, &vote)
+            .await
             .map(Json)
             .map_err(|_| Status::InternalServerError)
     } else {
@@ -316,12 +593,78 @@ pub async fn get_result(state: &State<AppState>, id: &str) -> Result<Json<VoteRe
     }
 }
 
-#[instrument(skip(state), fields(vote_id = %id), err)]
-#[get("/vote/<id>")]
-pub async fn get_vote(state: &State<AppState>, id: &str) -> Result<Json<Option<Vote>>, Status> {
-    let uuid = parse_vote_id(id).map_err(|_| Status::BadRequest)?;
-    VoteProcessor::fetch_vote_by_id(&state.db, uuid)
+#[instrument(skip(state, access_code), fields(vote_id = %id), err)]
+#[get("/vote/<id>/pairwise?<access_code>")]
+pub async fn get_pairwise(state: &State<AppState>, id: &str, access_code: Option<&str>) -> Result<Json<PairwiseAnalysis<String>>, Status> {
+    let vote = concluded_vote(state, id, access_code).await?;
+    VoteProcessor::get_pairwise(&state.db, &vote)
+        .await
+        .map(Json)
+        .map_err(|_| Status::InternalServerError)
+}
+
+#[instrument(skip(state, access_code), fields(vote_id = %id), err)]
+#[get("/vote/<id>/analytics?<access_code>")]
+pub async fn get_analytics(state: &State<AppState>, id: &str, access_code: Option<&str>) -> Result<Json<BallotAnalytics<String>>, Status> {
+    let vote = concluded_vote(state, id, access_code).await?;
+    VoteProcessor::get_analytics(&state.db, &vote)
+        .await
+        .map(Json)
+        .map_err(|_| Status::InternalServerError)
+}
+
+#[instrument(skip(state, access_code), fields(vote_id = %id), err)]
+#[get("/vote/<id>/ballots?<after>&<access_code>")]
+pub async fn get_ballots(state: &State<AppState>, id: &str, after: Option<i64>, access_code: Option<&str>) -> Result<Json<BallotPage>, Status> {
+    let vote = concluded_vote(state, id, access_code).await?;
+    VoteProcessor::ballot_page(&state.db, &vote, after.unwrap_or(0))
         .await
         .map(Json)
         .map_err(|_| Status::InternalServerError)
+}
+
+/// CSV repeats a ballot once per unit of weight, so weighted votes are only
+/// exported as BLT, which records the weight instead.
+#[instrument(skip(state, access_code), fields(vote_id = %id), err)]
+#[get("/vote/<id>/ballots.csv?<access_code>")]
+pub async fn get_ballots_csv(state: &State<AppState>, id: &str, access_code: Option<&str>) -> Result<(ContentType, TextStream<BoxStream<'static, String>>), Status> {
+    let vote = concluded_vote(state, id, access_code).await?;
+    if vote.weighted {
+        return Err(Status::UnprocessableEntity);
+    }
+    VoteProcessor::export_ballots(&state.db, &vote, BallotFormat::Csv)
+        .map(|rows| (ContentType::CSV, TextStream::from(rows.boxed())))
+        .map_err(|_| Status::InternalServerError)
+}
+
+#[instrument(skip(state, access_code), fields(vote_id = %id), err)]
+#[get("/vote/<id>/ballots.blt?<access_code>")]
+pub async fn get_ballots_blt(state: &State<AppState>, id: &str, access_code: Option<&str>) -> Result<(ContentType, TextStream<BoxStream<'static, String>>), Status> {
+    let vote = concluded_vote(state, id, access_code).await?;
+    VoteProcessor::export_ballots(&state.db, &vote, BallotFormat::Blt)
+        .map(|rows| (ContentType::Plain, TextStream::from(rows.boxed())))
+        .map_err(|_| Status::InternalServerError)
+}
+
+#[instrument(skip(state, access_code), fields(vote_id = %id), err)]
+#[get("/vote/<id>/compare-methods?<access_code>")]
+pub async fn compare_methods(state: &State<AppState>, id: &str, access_code: Option<&str>) -> Result<Json<Vec<MethodComparison>>, Status> {
+    let vote = concluded_vote(state, id, access_code).await?;
+    VoteProcessor::compare_methods(&state.db, &vote)
+        .await
+        .map(Json)
+        .map_err(|_| Status::InternalServerError)
+}
+
+#[instrument(skip(state, access_code), fields(vote_id = %id), err)]
+#[get("/vote/<id>?<access_code>")]
+pub async fn get_vote(state: &State<AppState>, id: &str, access_code: Option<&str>) -> Result<Json<Option<Vote>>, Status> {
+    let uuid = parse_vote_id(id).map_err(|_| Status::BadRequest)?;
+    let vote = VoteProcessor::get_vote_db(&state.db, uuid)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+    if let Some(vote) = &vote {
+        check_access(state, vote, access_code).await?;
+    }
+    Ok(Json(vote))
 }
\ No newline at end of file
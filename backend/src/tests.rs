@@ -0,0 +1,67 @@
+use time::{Duration, OffsetDateTime};
+use uuid::Uuid;
+use shared::models::{Vote, VoteState};
+use crate::processor::{ValidationError, VoteProcessor};
+
+fn vote(duration_hours: i32, duration_minutes: i32) -> Vote {
+    Vote {
+        id: Uuid::nil(),
+        title: "Lunch".into(),
+        description: String::new(),
+        options: vec!["A".into(), "B".into()],
+        voting_ends_at: OffsetDateTime::now_utc() + Duration::hours(1),
+        ballot_count: 0,
+        duration_hours,
+        duration_minutes,
+        user_fingerprint: String::new(),
+        seats: 1,
+        winner_method: Default::default(),
+        max_score: 5,
+        weighted: false,
+        blank_policy: Default::default(),
+        withdrawn_options: Vec::new(),
+        results_visibility: Default::default(),
+        opens_at: None,
+        visibility: Default::default(),
+        tiebreak_seed: None,
+    }
+}
+
+#[test]
+fn test_state_transitions() {
+    use VoteState::*;
+    let states = [Scheduled, Active, Concluded];
+    let allowed = [(Scheduled, Scheduled), (Scheduled, Active), (Active, Active), (Active, Concluded)];
+    for from in states {
+        for to in states {
+            let result = VoteProcessor::check_transition(from, to);
+            if allowed.contains(&(from, to)) {
+                assert!(result.is_ok(), "{from:?} -> {to:?}");
+            } else {
+                assert!(matches!(result, Err(ValidationError::InvalidStateTransition(..))), "{from:?} -> {to:?}");
+            }
+        }
+    }
+}
+
+#[test]
+fn test_extended_duration() {
+    assert!(matches!(VoteProcessor::extended_duration(&vote(1, 50), 0, 20), Ok((2, 10))));
+    assert!(matches!(VoteProcessor::extended_duration(&vote(0, 30), 2, 0), Ok((2, 30))));
+    assert!(matches!(VoteProcessor::extended_duration(&vote(166, 0), 1, 59), Ok((167, 59))));
+
+    for (hours, minutes) in [(0, 0), (-1, 30), (1, -1)] {
+        assert!(matches!(VoteProcessor::extended_duration(&vote(1, 0), hours, minutes), Err(ValidationError::InvalidDuration)));
+    }
+    for (hours, minutes) in [(0, 1), (i32::MAX, i32::MAX), (1, 0)] {
+        assert!(matches!(VoteProcessor::extended_duration(&vote(167, 59), hours, minutes), Err(ValidationError::DurationTooLong)));
+    }
+}
+
+#[test]
+fn test_access_code_hash() {
+    let hash = VoteProcessor::hash_credential("open sesame");
+    assert!(VoteProcessor::check_access_code_hash(Some(&hash), "open sesame").is_ok());
+    assert!(matches!(VoteProcessor::check_access_code_hash(Some(&hash), "open sesame "), Err(ValidationError::InvalidAccessCode)));
+    assert!(matches!(VoteProcessor::check_access_code_hash(None, "open sesame"), Err(ValidationError::InvalidAccessCode)));
+}
@@ -10,6 +10,7 @@ mod home;
 mod vote_status;
 mod vote_option_manager;
 mod vote_create;
+mod management;
 mod config;
 pub mod hcaptcha;
 pub mod render_results;
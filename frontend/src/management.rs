@@ -0,0 +1,65 @@
+//! Per-vote secrets kept in local storage: management tokens for votes created
+//! in this browser, since the server only ever hands them out once, and the
+//! access codes of private votes.
+use gloo_net::http::Request;
+use uuid::Uuid;
+use crate::config::CONFIG;
+
+const MANAGEMENT_TOKEN: &str = "management_token";
+const ACCESS_CODE: &str = "access_code";
+
+fn key(kind: &str, vote_id: &str) -> String {
+    format!("{kind}:{vote_id}")
+}
+
+fn storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok()?
+}
+
+fn save(kind: &str, vote_id: &str, value: &str) {
+    if let Some(storage) = storage() {
+        let _ = storage.set_item(&key(kind, vote_id), value);
+    }
+}
+
+fn load(kind: &str, vote_id: &str) -> Option<String> {
+    storage()?.get_item(&key(kind, vote_id)).ok()?
+}
+
+pub fn save_token(vote_id: Uuid, token: &str) {
+    save(MANAGEMENT_TOKEN, &vote_id.to_string(), token);
+}
+
+pub fn token(vote_id: &str) -> Option<String> {
+    load(MANAGEMENT_TOKEN, vote_id)
+}
+
+pub fn save_access_code(vote_id: &str, access_code: &str) {
+    save(ACCESS_CODE, vote_id, access_code);
+}
+
+pub fn access_code(vote_id: &str) -> Option<String> {
+    load(ACCESS_CODE, vote_id)
+}
+
+/// The query string passing the saved access code to the API; empty if none is saved.
+pub fn access_query(vote_id: &str) -> String {
+    query([(ACCESS_CODE, access_code(vote_id))])
+}
+
+/// Builds the request for a vote's result. A saved management token goes in a
+/// header rather than the URL, and shows the creator results kept from everyone else.
+pub fn result_request(vote_id: &str) -> Request {
+    let request = Request::get(&format!("{}/vote/{}/result{}", CONFIG.api_base_url, vote_id, access_query(vote_id)));
+    match token(vote_id) {
+        Some(token) => request.header("X-Management-Token", &token),
+        None => request,
+    }
+}
+
+fn query<const N: usize>(params: [(&str, Option<String>); N]) -> String {
+    let pairs: Vec<String> = params.into_iter()
+        .filter_map(|(name, value)| value.map(|value| format!("{name}={}", js_sys::encode_uri_component(&value))))
+        .collect();
+    if pairs.is_empty() { String::new() } else { format!("?{}", pairs.join("&")) }
+}
@@ -1,7 +1,12 @@
 use yew::prelude::*;
 use yew_router::prelude::*;
-use shared::models::{Vote, VoteResult, VoteOptionStats, HeadToHeadResult};
-use crate::{styles::*, Route};
+use shared::models::{BallotPage, Vote, VoteResult, VoteOptionStats, HeadToHeadResult, MethodComparison};
+use shared::analytics::BallotAnalytics;
+use shared::star_logic::{
+    HeadToHeadMatchup, PairwiseAnalysis, PlaceReason, RankedPlace, TiebreakRule, TiebreakStage, TiebreakStep,
+    TabulationOutcome, TiebreakTrace, VictoryMargin, WinnerRound,
+};
+use crate::{config::CONFIG, management, styles::*, Route};
 use std::cmp::Ordering;
 
 fn render_head_to_head_results(head_to_head: &HeadToHeadResult) -> Html {
@@ -22,10 +27,71 @@ fn render_head_to_head_results(head_to_head: &HeadToHeadResult) -> Html {
     }
 }
 
+fn render_runner_up_matchups(matchups: &[HeadToHeadMatchup<String>]) -> Html {
+    if matchups.is_empty() {
+        return html! {};
+    }
+
+    html! {
+        <div class="bg-gray-800/30 rounded-lg p-4">
+            <div class="font-medium mb-3 text-sm text-gray-300">{"Winner Against Other Contenders"}</div>
+            <div class="space-y-2">
+                {for matchups.iter().map(|matchup| html! {
+                    <div class="relative text-sm">
+                        <div class="max-w-full break-words pr-20">
+                            {format!("vs. {}", matchup.candidate2)}
+                        </div>
+                        <div class="absolute right-0 top-0 font-mono">
+                            {format!("{} – {}", matchup.votes1, matchup.votes2)}
+                        </div>
+                    </div>
+                })}
+            </div>
+        </div>
+    }
+}
+
+fn render_margin(margin: &VictoryMargin<String>, winner: &str, head_to_head: &HeadToHeadResult) -> Html {
+    let runner_up = if head_to_head.finalist1 == winner { &head_to_head.finalist2 } else { &head_to_head.finalist1 };
+    let plural = |n: u64| if n == 1 { "ballot" } else { "ballots" };
+
+    html! {
+        <div class="bg-gray-800/30 rounded-lg p-4">
+            <div class="font-medium mb-3 text-sm text-gray-300">{"Margin of Victory"}</div>
+            <ul class="list-disc list-inside space-y-1 text-sm break-words">
+                <li>
+                    {format!(
+                        "{} would have won with {} changed {} or {} more {} preferring them.",
+                        runner_up,
+                        margin.runoff_ballots_changed, plural(margin.runoff_ballots_changed),
+                        margin.runoff_ballots_added, plural(margin.runoff_ballots_added)
+                    )}
+                </li>
+                {margin.finalist_margin.as_ref().map_or(html! {}, |finalist| html! {
+                    <li>
+                        {match finalist.ballots_changed {
+                            Some(changed) => format!(
+                                "{} would have reached the runoff with {} changed {} or {} more {}.",
+                                finalist.challenger, changed, plural(changed),
+                                finalist.ballots_added, plural(finalist.ballots_added)
+                            ),
+                            None => format!(
+                                "{} would have needed {} more {} to reach the runoff.",
+                                finalist.challenger, finalist.ballots_added, plural(finalist.ballots_added)
+                            ),
+                        }}
+                    </li>
+                })}
+            </ul>
+        </div>
+    }
+}
+
 fn render_winner_details(
     _winner: &str,
     _head_to_head: &HeadToHeadResult,
-    is_tie: bool,
+    tiebreak: Option<&TiebreakTrace<String>>,
+    max_score: i8,
     (finalist1, f1_nonzero, f1_stats): (&str, usize, &VoteOptionStats),
     (finalist2, f2_nonzero, f2_stats): (&str, usize, &VoteOptionStats)
 ) -> Html {
@@ -37,40 +103,49 @@ fn render_winner_details(
                     <div class="max-w-full break-words mb-1">{finalist1}</div>
                     <div class="text-sm text-gray-400 ml-3 space-y-0.5">
                         {format!("{} non-zero votes", f1_nonzero)}
-                        <div>{format!("{} five-star ratings", f1_stats.frequency.get(&5).unwrap_or(&0))}</div>
+                        <div>{format!("{} ratings of {}", f1_stats.frequency.get(&max_score).unwrap_or(&0), max_score)}</div>
                     </div>
                 </div>
                 <div>
                     <div class="max-w-full break-words mb-1">{finalist2}</div>
                     <div class="text-sm text-gray-400 ml-3 space-y-0.5">
                         {format!("{} non-zero votes", f2_nonzero)}
-                        <div>{format!("{} five-star ratings", f2_stats.frequency.get(&5).unwrap_or(&0))}</div>
+                        <div>{format!("{} ratings of {}", f2_stats.frequency.get(&max_score).unwrap_or(&0), max_score)}</div>
                     </div>
                 </div>
-                {if is_tie {
-                    html! {
-                        <div class="mt-3 text-sm text-yellow-300/90">
-                            {"Tie resolved by tiebreaker rules"}
-                        </div>
-                    }
-                } else {
-                    html! {}
-                }}
+                {tiebreak.map_or(html! {}, |trace| render_tiebreak_stage(trace, TiebreakStage::Runoff))}
             </div>
         </div>
     }
 }
 
-pub fn render_results_view(vote: &Vote, result: &VoteResult) -> Html {
+pub fn render_results_view(
+    vote: &Vote,
+    result: &VoteResult,
+    pairwise: Option<&PairwiseAnalysis<String>>,
+    comparison: Option<&[MethodComparison]>,
+    analytics: Option<&BallotAnalytics<String>>,
+    ballots: Option<&BallotPage>,
+) -> Html {
     html! {
         <div class={CONTAINER_SM}>
             <div class={CARD}>
                 <h1 class={classes!(HEADING_MD, "break-words")}>{&vote.title}</h1>
                 <p class={classes!("mb-2", "text-white", "break-words")}>{&vote.description}</p>
+                {render_provisional(result)}
                 {render_vote_duration(result)}
-                {render_runoff_round(result.winner.as_deref(), result.error.as_deref(), result)}
+                {render_withdrawals(result)}
+                if result.seat_rounds.is_empty() {
+                    {render_runoff_round(result, max_score(vote))}
+                } else {
+                    {render_elected_seats(&result.seat_rounds)}
+                }
+                {render_ranking(&result.ranking)}
                 {render_score_distributions(result, vote)}
-                {render_ballots(vote, result)}
+                {pairwise.map_or(html! {}, |pairwise| render_pairwise(pairwise, result.winner.as_deref()))}
+                {comparison.map_or(html! {}, |comparison| render_method_comparison(comparison, result.winner.as_deref()))}
+                {analytics.map_or(html! {}, render_analytics)}
+                {render_ballots(vote, result, ballots)}
                 <div class="mt-6 flex justify-center">
                     <Link<Route> to={Route::Home} 
                         classes={classes!(button_primary(false))}>
@@ -82,101 +157,138 @@ pub fn render_results_view(vote: &Vote, result: &VoteResult) -> Html {
     }
 }
 
-fn render_tie_resolution(tied_options: &[(&str, &VoteOptionStats)]) -> Html {
-    let first_stats = &tied_options[0].1;
-    let all_identical = tied_options.iter().all(|(_, stats)| {
-        stats.total_score == first_stats.total_score && 
-        stats.frequency == first_stats.frequency
-    });
- 
-    if all_identical {
-        return html! {
-            <div class="mt-4 pt-4 border-t border-blue-700">
-                <p class="font-medium mb-2">{"True Tie - Identical Statistics:"}</p>
-                <div class="ml-4 space-y-3">
-                    <ol class="list-decimal list-inside space-y-1">
-                        <li>{format_scores(tied_options)}</li>
-                        <li>{"All options have identical vote distributions"}</li>
-                    </ol>
-                    <p class="font-medium">{"Unable to determine advancement - true tie"}</p>
-                </div>
-            </div>
-        };
+fn render_provisional(result: &VoteResult) -> Html {
+    if !result.provisional {
+        return html! {};
     }
- 
-    let nonzero_counts: Vec<_> = tied_options.iter()
-        .map(|(name, stats)| (*name, (1..=5).map(|i| stats.frequency.get(&i).unwrap_or(&0)).sum()))
-        .collect();
-    let max_nonzero = nonzero_counts.iter().map(|(_, count)| count).max().unwrap_or(&0);
-    let nonzero_winners: Vec<_> = nonzero_counts.iter()
-        .filter(|(_, count)| count == max_nonzero)
-        .collect();
-    let five_star_counts: Vec<_> = tied_options.iter()
-        .map(|(name, stats)| (*name, *stats.frequency.get(&5).unwrap_or(&0)))
-        .collect();
-    let max_fives = five_star_counts.iter().map(|(_, count)| count).max().unwrap_or(&0);
-    let five_winners: Vec<_> = five_star_counts.iter()
-        .filter(|(_, count)| count == max_fives)
-        .collect();
- 
+    html! {
+        <div class={classes!(alert_style("warning"), "mb-4")}>
+            <p>{"Provisional results: voting is still open, so these may change."}</p>
+        </div>
+    }
+}
+
+fn render_withdrawals(result: &VoteResult) -> Html {
+    if result.withdrawn.is_empty() {
+        return html! {};
+    }
+    let original_winners = result.original.as_ref()
+        .map(|original| original.winners.join(", "))
+        .filter(|winners| !winners.is_empty());
+
+    html! {
+        <div class={classes!(alert_style("warning"), "mb-4", "break-words")}>
+            <p>{format!("Withdrawn after voting ended: {}. These results ignore their scores.", result.withdrawn.join(", "))}</p>
+            {original_winners.map_or(html! {}, |winners| html! {
+                <p class="text-sm mt-1">{format!("Before the withdrawal: {}", winners)}</p>
+            })}
+        </div>
+    }
+}
+
+fn render_tiebreak_stage(trace: &TiebreakTrace<String>, stage: TiebreakStage) -> Html {
+    let steps: Vec<_> = trace.stage(stage).collect();
+    let Some(last) = steps.last() else { return html! {} };
+
+    let title = match stage {
+        TiebreakStage::FirstFinalist => "First Finalist Tie Resolution:",
+        TiebreakStage::SecondFinalist => "Second Finalist Tie Resolution:",
+        TiebreakStage::Runoff => "Runoff Tie Resolution:",
+    };
+    let verb = if stage == TiebreakStage::Runoff { "wins" } else { "advances" };
+
     html! {
         <div class="mt-4 pt-4 border-t border-blue-700">
-            <p class="font-medium mb-2">{
-                if tied_options.len() > 2 {
-                    format!("{}-Way Second Place Tie Resolution:", tied_options.len())
-                } else {
-                    "Second Place Resolution:".to_string()
-                }
-            }</p>
+            <p class="font-medium mb-2">{title}</p>
             <div class="ml-4 space-y-3">
                 <ol class="list-decimal list-inside space-y-1">
-                    <li>{format_scores(tied_options)}</li>
-                    <li>{format_nonzero_votes(&nonzero_counts)}</li>
-                    <li>{format_five_star_ratings(&five_star_counts)}</li>
+                    {for steps.iter().map(|step| html! { <li>{format_tiebreak_step(step)}</li> })}
                 </ol>
-                <p class="font-medium">{determine_winner_text(&nonzero_winners, &five_winners)}</p>
+                <p class="font-medium">{
+                    match &last.advanced[..] {
+                        [advanced] => format!("{} {} ({})", advanced, verb, rule_name(last.rule)),
+                        _ => "Unable to resolve tie".to_string(),
+                    }
+                }</p>
             </div>
         </div>
     }
 }
 
-fn format_scores(options: &[(&str, &VoteOptionStats)]) -> String {
-    format!("Total scores: {}", options.iter().enumerate()
-        .map(|(i, (name, stats))| format!("{}: {} points{}", 
-            name, stats.total_score,
-            if i < options.len() - 1 { ", " } else { "" }))
-        .collect::<String>())
+fn rule_name(rule: TiebreakRule) -> &'static str {
+    match rule {
+        TiebreakRule::HeadToHead => "by head-to-head preference",
+        TiebreakRule::ScoreTotal => "by total score",
+        TiebreakRule::TopScoreCount => "by top-score ratings",
+        TiebreakRule::RandomLot => "by random lot",
+        TiebreakRule::DecidedByRunoff => "runoff outcome unaffected",
+    }
 }
 
-fn format_nonzero_votes(counts: &[(&str, usize)]) -> String {
-    format!("Non-zero votes: {}", counts.iter().enumerate()
-        .map(|(i, (name, count))| format!("{}: {}{}", 
-            name, count,
-            if i < counts.len() - 1 { ", " } else { "" }))
-        .collect::<String>())
+fn format_tiebreak_step(step: &TiebreakStep<String>) -> String {
+    let names = step.candidates.iter()
+        .map(|(name, _)| name.as_str())
+        .collect::<Vec<_>>()
+        .join(", ");
+    let values = step.candidates.iter()
+        .map(|(name, value)| format!("{}: {}", name, value))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    match step.rule {
+        TiebreakRule::HeadToHead => format!("Head-to-head wins among tied options: {}", values),
+        TiebreakRule::ScoreTotal => format!("Total scores: {}", values),
+        TiebreakRule::TopScoreCount => format!("Top-score ratings: {}", values),
+        TiebreakRule::RandomLot => format!("Random lot drawn between: {}", names),
+        TiebreakRule::DecidedByRunoff => format!("The first finalist beats each of {} head-to-head", names),
+    }
 }
 
-fn format_five_star_ratings(counts: &[(&str, usize)]) -> String {
-    format!("Five-star ratings: {}", counts.iter().enumerate()
-        .map(|(i, (name, count))| format!("{}: {}{}", 
-            name, count,
-            if i < counts.len() - 1 { ", " } else { "" }))
-        .collect::<String>())
+fn render_ranking(ranking: &[RankedPlace<String>]) -> Html {
+    if ranking.is_empty() {
+        return html! {};
+    }
+
+    html! {
+        <div class={combine_classes(STATS_CARD, STATS_CARD_INFO)}>
+            <h3 class={HEADING_SM}>{"Full Ranking"}</h3>
+            <p class="text-sm text-gray-300 mb-3">
+                {"Each place goes to the STAR winner among the options not already placed."}
+            </p>
+            <ol class="space-y-2">
+                {for ranking.iter().map(|place| html! {
+                    <li class="flex items-baseline">
+                        <span class="font-mono text-gray-400 w-10 shrink-0 text-right pr-2">{format!("{}.", place.place)}</span>
+                        <div class="min-w-0">
+                            <div class="font-medium break-words">{&place.option}</div>
+                            <div class="text-sm text-gray-400">{format_place_reason(&place.reason)}</div>
+                        </div>
+                    </li>
+                })}
+            </ol>
+        </div>
+    }
 }
 
-fn determine_winner_text(nonzero_winners: &[&(&str, usize)], five_winners: &[&(&str, usize)]) -> String {
-    if nonzero_winners.len() == 1 && nonzero_winners[0].1 > 0 {
-        format!("{} advances (by non-zero votes)", nonzero_winners[0].0)
-    } else if five_winners.len() == 1 && five_winners[0].1 > 0 {
-        format!("{} advances (by five-star ratings)", five_winners[0].0)
-    } else if !five_winners.is_empty() && five_winners[0].1 > 0 {
-        format!("{} advances (by tie-breaking rules)", five_winners[0].0)
-    } else {
-        "Unable to determine perfect tie".to_string()
+fn format_place_reason(reason: &PlaceReason<String>) -> String {
+    match reason {
+        PlaceReason::WonRunoff { opponent, votes: (won, lost) } =>
+            format!("Preferred over {} by {} – {}", opponent, won, lost),
+        PlaceReason::RunoffTiebreak { opponent, rule } =>
+            format!("Tied with {} in the runoff, decided {}", opponent, rule_name(*rule)),
+        PlaceReason::LastRemaining => "Last remaining option".to_string(),
+        PlaceReason::UnresolvedTie { tied } =>
+            format!("Unresolved tie between {}; the places from here on are undecided", tied.join(", ")),
     }
 }
 
+fn max_score(vote: &Vote) -> i8 {
+    i8::try_from(vote.max_score).unwrap_or(5)
+}
+
 fn render_score_distributions(result: &VoteResult, vote: &Vote) -> Html {
+    let max_score = max_score(vote);
+    let columns = format!("grid-template-columns: repeat({}, minmax(0, 1fr))", max_score + 1);
     let mut options: Vec<_> = vote.options.iter()
         .filter_map(|opt| result.stats.option_scores.get(opt)
             .map(|stats| (opt.to_string(), stats)))
@@ -184,17 +296,6 @@ fn render_score_distributions(result: &VoteResult, vote: &Vote) -> Html {
     options.sort_by(|(_, a), (_, b)|
         b.average_score.partial_cmp(&a.average_score).unwrap_or(Ordering::Equal));
 
-    let second_place_ties = if options.len() >= 2 {
-        options.iter()
-            .skip(1)
-            .take_while(|(_, stats)| 
-                (stats.average_score - options[1].1.average_score).abs() < f64::EPSILON)
-            .map(|(opt, stats)| (opt.as_str(), *stats))
-            .collect::<Vec<_>>()
-    } else {
-        Vec::new()
-    };
-
     html! {
         <div class={combine_classes(STATS_CARD, STATS_CARD_INFO)}>
             <h3 class={HEADING_SM}>{"Complete Score Distribution"}</h3>
@@ -209,9 +310,10 @@ fn render_score_distributions(result: &VoteResult, vote: &Vote) -> Html {
                         </div>
                         <div class="text-sm text-gray-400 mb-3">
                             {format!("Total Score: {}", stats.total_score)}
+                            {if stats.abstentions > 0 { format!(" · No opinion: {}", stats.abstentions) } else { String::new() }}
                         </div>
-                        <div class="grid grid-cols-6 text-center">
-                            {for (0..=5).map(|score| {
+                        <div class="grid text-center" style={columns.clone()}>
+                            {for (0..=max_score).map(|score| {
                                 let count = stats.frequency.get(&score).copied().unwrap_or(0);
                                 html! {
                                     <div>
@@ -228,30 +330,214 @@ fn render_score_distributions(result: &VoteResult, vote: &Vote) -> Html {
                     </div>
                 })}
             </div>
-            {if second_place_ties.len() > 1 {
-                render_tie_resolution(&second_place_ties)
+            {if let Some(trace) = &result.tiebreak {
+                html! {
+                    <>
+                        {render_tiebreak_stage(trace, TiebreakStage::FirstFinalist)}
+                        {render_tiebreak_stage(trace, TiebreakStage::SecondFinalist)}
+                    </>
+                }
             } else {
                 html! {}
             }}
-            {render_tiebreak_rules()}
+            {render_tiebreak_rules(vote.tiebreak_seed)}
         </div>
     }
 }
 
-fn render_tiebreak_rules() -> Html {
+fn render_tiebreak_rules(seed: Option<u64>) -> Html {
     html! {
         <div class="mt-4 pt-4 border-t border-blue-700">
             <h4 class={combine_classes(HEADING_SM, "mb-2")}>{"Tie-Breaking Rules"}</h4>
             <ol class="list-decimal list-inside space-y-1 text-sm">
-                <li>{"Most unique voters (non-zero)"}</li>
-                <li>{"Most high scores (5s, then 4s)"}</li>
-                <li>{"Fewest low scores (0s, then 1s)"}</li>
+                <li>{"Scoring round: most head-to-head wins among the tied options"}</li>
+                <li>{"Runoff: highest total score"}</li>
+                <li>{"Most top-score ratings"}</li>
+                <li>{"Random lot, from a seed drawn secretly when the vote was created"}</li>
             </ol>
+            {seed.map_or(html! {}, |seed| html! {
+                <p class="text-sm text-gray-400 mt-2">{format!("Tiebreak seed: {}", seed)}</p>
+            })}
         </div>
     }
 }
 
-fn render_ballots(vote: &Vote, result: &VoteResult) -> Html {
+fn render_pairwise(pairwise: &PairwiseAnalysis<String>, star_winner: Option<&str>) -> Html {
+    let condorcet_text = match (pairwise.condorcet_winner.as_deref(), star_winner) {
+        (Some(condorcet), Some(star)) if condorcet == star =>
+            format!("{} is also the Condorcet winner", star),
+        (Some(condorcet), Some(star)) =>
+            format!("{} is the Condorcet winner, while STAR elected {}", condorcet, star),
+        (Some(condorcet), None) => format!("Condorcet winner: {}", condorcet),
+        (None, _) => "No Condorcet winner: no option beats every other head-to-head".to_string(),
+    };
+
+    html! {
+        <div class={combine_classes(STATS_CARD, STATS_CARD_INFO)}>
+            <h3 class={HEADING_SM}>{"Pairwise Preferences"}</h3>
+            <p class="text-sm text-gray-300 mb-3">
+                {"Each cell counts the voters who scored the row option above the column option."}
+            </p>
+            <div class="rounded-lg border border-blue-700 overflow-x-auto mb-4">
+                <table class="w-full text-sm">
+                    <thead class="bg-blue-800/50">
+                        <tr>
+                            <th class="px-2 py-1 border-b border-r border-blue-700 text-center w-12"/>
+                            {for (0..pairwise.options.len()).map(|j| html! {
+                                <th class="px-2 py-1 border-b border-r last:border-r-0 border-blue-700 text-center w-12">
+                                    {j + 1}
+                                </th>
+                            })}
+                        </tr>
+                    </thead>
+                    <tbody>
+                        {for pairwise.options.iter().enumerate().map(|(i, opt)| html! {
+                            <tr>
+                                <td class="px-2 py-1 border-r border-blue-700 text-center font-mono" title={opt.clone()}>
+                                    {i + 1}
+                                </td>
+                                {for (0..pairwise.options.len()).map(|j| {
+                                    let cell_class = if i == j {
+                                        "text-gray-500"
+                                    } else if pairwise.beats(i, j) {
+                                        "text-green-300 font-semibold"
+                                    } else {
+                                        ""
+                                    };
+                                    html! {
+                                        <td class={classes!("px-2", "py-1", "border-r", "last:border-r-0", "border-blue-700", "text-center", cell_class)}>
+                                            {if i == j { "-".to_string() } else { pairwise.matrix[i][j].to_string() }}
+                                        </td>
+                                    }
+                                })}
+                            </tr>
+                        })}
+                    </tbody>
+                </table>
+            </div>
+            <div class="space-y-1 mb-4 text-sm">
+                {for pairwise.options.iter().enumerate().map(|(i, opt)| html! {
+                    <div class="flex items-baseline">
+                        <span class="font-mono text-gray-400 w-8 shrink-0 text-right pr-1">{format!("{}.", i + 1)}</span>
+                        <span class="min-w-0 break-words">{opt}</span>
+                    </div>
+                })}
+            </div>
+            <ul class="list-disc list-inside space-y-1 text-sm">
+                <li>{condorcet_text}</li>
+                {if let Some(loser) = &pairwise.condorcet_loser {
+                    html! { <li>{format!("Condorcet loser: {}", loser)}</li> }
+                } else {
+                    html! {}
+                }}
+                <li>{format!("Smith set: {}", pairwise.smith_set.join(", "))}</li>
+            </ul>
+        </div>
+    }
+}
+
+fn render_method_comparison(comparison: &[MethodComparison], star_winner: Option<&str>) -> Html {
+    html! {
+        <div class={combine_classes(STATS_CARD, STATS_CARD_INFO)}>
+            <h3 class={HEADING_SM}>{"Other Voting Methods"}</h3>
+            <p class="text-sm text-gray-300 mb-3">
+                {"The same ballots counted under other methods. Approval counts scores of 3 or more; Plurality counts each voter's single favorite."}
+            </p>
+            <div class="space-y-2">
+                {for comparison.iter().map(|entry| {
+                    let agrees = entry.winner.is_some() && entry.winner.as_deref() == star_winner;
+                    html! {
+                        <div class="relative text-sm">
+                            <div class="font-medium">{&entry.method}</div>
+                            <div class={classes!("max-w-full", "break-words", "ml-3", if agrees { "text-green-300" } else { "text-gray-300" })}>
+                                {match (&entry.winner, &entry.error) {
+                                    (Some(winner), _) => winner.clone(),
+                                    (None, Some(error)) => error.clone(),
+                                    (None, None) => "No winner".to_string(),
+                                }}
+                            </div>
+                        </div>
+                    }
+                })}
+            </div>
+        </div>
+    }
+}
+
+fn render_analytics(analytics: &BallotAnalytics<String>) -> Html {
+    let n = analytics.options.len();
+
+    html! {
+        <div class={combine_classes(STATS_CARD, STATS_CARD_INFO)}>
+            <h3 class={HEADING_SM}>{"Ballot Analytics"}</h3>
+            <ul class="list-disc list-inside space-y-1 text-sm mb-4">
+                <li>{format!("Bullet ballots (one nonzero score): {:.0}%", analytics.bullet_share * 100.0)}</li>
+                <li>{format!("Exaggerated ballots (only lowest and highest scores): {:.0}%", analytics.exaggerated_share * 100.0)}</li>
+            </ul>
+            <p class="text-sm text-gray-300 mb-3">
+                {"Score correlation between options: near 1 means voters scored them alike, near -1 means voters who liked one disliked the other."}
+            </p>
+            <div class="rounded-lg border border-blue-700 overflow-x-auto mb-4">
+                <table class="w-full text-sm">
+                    <thead class="bg-blue-800/50">
+                        <tr>
+                            <th class="px-2 py-1 border-b border-r border-blue-700 text-center w-12"/>
+                            {for (0..n).map(|j| html! {
+                                <th class="px-2 py-1 border-b border-r last:border-r-0 border-blue-700 text-center w-12">
+                                    {j + 1}
+                                </th>
+                            })}
+                        </tr>
+                    </thead>
+                    <tbody>
+                        {for analytics.options.iter().enumerate().map(|(i, opt)| html! {
+                            <tr>
+                                <td class="px-2 py-1 border-r border-blue-700 text-center font-mono" title={opt.clone()}>
+                                    {i + 1}
+                                </td>
+                                {for (0..n).map(|j| {
+                                    let value = analytics.correlation[i][j];
+                                    let cell_class = match value {
+                                        _ if i == j => "text-gray-500",
+                                        Some(r) if r >= 0.5 => "text-green-300 font-semibold",
+                                        Some(r) if r <= -0.5 => "text-red-300 font-semibold",
+                                        _ => "",
+                                    };
+                                    html! {
+                                        <td class={classes!("px-2", "py-1", "border-r", "last:border-r-0", "border-blue-700", "text-center", cell_class)}>
+                                            {match value {
+                                                _ if i == j => "-".to_string(),
+                                                Some(r) => format!("{:.2}", r),
+                                                None => "n/a".to_string(),
+                                            }}
+                                        </td>
+                                    }
+                                })}
+                            </tr>
+                        })}
+                    </tbody>
+                </table>
+            </div>
+            <div class="font-medium mb-2 text-sm text-gray-300">{"Ballot Clusters"}</div>
+            <div class="space-y-2">
+                {for analytics.clusters.iter().enumerate().map(|(i, cluster)| {
+                    let mut centroid = cluster.centroid.clone();
+                    centroid.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(Ordering::Equal));
+                    html! {
+                        <div class="text-sm">
+                            <div class="font-medium">{format!("Cluster {} ({:.0}% of ballots)", i + 1, cluster.share * 100.0)}</div>
+                            <div class="ml-3 text-gray-300 break-words">
+                                {centroid.iter().map(|(opt, score)| format!("{} {:.1}", opt, score)).collect::<Vec<_>>().join(" · ")}
+                            </div>
+                        </div>
+                    }
+                })}
+            </div>
+        </div>
+    }
+}
+
+fn render_ballots(vote: &Vote, result: &VoteResult, ballots: Option<&BallotPage>) -> Html {
     let mut options: Vec<_> = vote.options.iter()
         .filter_map(|opt| result.stats.option_scores.get(opt)
             .map(|stats| (opt.as_str(), stats)))
@@ -261,11 +547,35 @@ fn render_ballots(vote: &Vote, result: &VoteResult) -> Html {
         b.average_score.partial_cmp(&a.average_score).unwrap_or(Ordering::Equal));
 
     let ordered_options: Vec<&str> = options.iter().map(|(opt, _)| *opt).collect();
+    let columns: Vec<usize> = ordered_options.iter()
+        .filter_map(|opt| vote.options.iter().position(|o| o == opt))
+        .collect();
+    // CSV has no room for weights, so weighted votes download as BLT.
+    let (format, label) = if vote.weighted { ("blt", "BLT") } else { ("csv", "CSV") };
 
     html! {
         <div class="mt-4">
-            {render_ballot_header(&ordered_options)}
-            {render_ballot_table(&ordered_options, vote)}
+            if let Some(ballots) = ballots {
+                {render_ballot_header(&ordered_options)}
+                {render_ballot_table(&columns, ballots)}
+                if ballots.next.is_some() {
+                    <p class={combine_classes(TEXT_MUTED, "mt-2")}>
+                        {format!("Showing the first {} of {} ballots; download them all below.", ballots.ballots.len(), vote.ballot_count)}
+                    </p>
+                }
+            }
+            if !result.provisional {
+                <div class="mt-2 text-right">
+                    <a href={format!(
+                        "{}/vote/{}/ballots.{}{}",
+                        CONFIG.api_base_url, vote.id, format, management::access_query(&vote.id.to_string())
+                    )}
+                        download={format!("ballots.{format}")}
+                        class={combine_classes(TEXT_MUTED, "underline hover:text-gray-200")}>
+                        {format!("Download ballots ({label})")}
+                    </a>
+                </div>
+            }
         </div>
     }
 }
@@ -291,7 +601,8 @@ fn render_ballot_header(ordered_options: &[&str]) -> Html {
     }
 }
 
-fn render_ballot_table(ordered_options: &[&str], vote: &Vote) -> Html {
+/// `columns` holds, for each column, the index of its option in the vote.
+fn render_ballot_table(columns: &[usize], page: &BallotPage) -> Html {
     html! {
         <div class="rounded-lg border border-gray-300 overflow-x-auto">
             <table class="w-full text-sm">
@@ -300,22 +611,22 @@ fn render_ballot_table(ordered_options: &[&str], vote: &Vote) -> Html {
                         <th class="px-2 py-1 border-b border-r border-gray-300 text-center w-16 text-white">
                             {"Ballot"}
                         </th>
-                        {for ordered_options.iter().enumerate().map(|(i, _)| html! {
+                        {for (1..=columns.len()).map(|i| html! {
                             <th class="px-2 py-1 border-b border-r last:border-r-0 border-gray-300 text-center w-12 text-white">
-                                {i + 1}
+                                {i}
                             </th>
                         })}
                     </tr>
                 </thead>
                 <tbody>
-                    {for vote.ballots.iter().enumerate().map(|(i, ballot)| html! {
+                    {for page.ballots.iter().enumerate().map(|(i, ballot)| html! {
                         <tr class="hover:bg-gray-700/30">
                             <td class="px-2 py-1 border-r border-gray-300 text-center text-white font-mono text-xs">
                                 {format!("#{}", i + 1)}
                             </td>
-                            {for ordered_options.iter().map(|opt| html! {
+                            {for columns.iter().map(|&column| html! {
                                 <td class="px-2 py-1 border-r last:border-r-0 border-gray-300 text-center text-white">
-                                    {ballot.scores.get(*opt).map_or("-".to_string(), |score| score.to_string())}
+                                    {ballot.scores.get(column).copied().flatten().map_or("-".to_string(), |score| score.to_string())}
                                 </td>
                             })}
                         </tr>
@@ -326,26 +637,25 @@ fn render_ballot_table(ordered_options: &[&str], vote: &Vote) -> Html {
     }
 }
 
-fn render_runoff_round(winner: Option<&str>, error: Option<&str>, result: &VoteResult) -> Html {
-    match (&result.head_to_head, winner, error) {
-        (Some(head_to_head), Some(_), None) => {
-            render_winner_section(result.winner.as_deref().unwrap(), head_to_head, result)
+fn render_runoff_round(result: &VoteResult, max_score: i8) -> Html {
+    match (&result.head_to_head, result.winner.as_deref(), &result.outcome) {
+        (Some(head_to_head), Some(winner), TabulationOutcome::Winner) => {
+            render_winner_section(winner, head_to_head, result, max_score)
         }
-        (_, _, Some(error_msg)) => render_error_section(error_msg),
-        _ => html! {}
+        (_, _, TabulationOutcome::Winner) => html! {},
+        (_, _, outcome) => render_outcome_section(outcome),
     }
 }
 
-fn render_winner_section(winner: &str, head_to_head: &HeadToHeadResult, result: &VoteResult) -> Html {
-    let is_tie = head_to_head.finalist1_votes == head_to_head.finalist2_votes;
+fn render_winner_section(winner: &str, head_to_head: &HeadToHeadResult, result: &VoteResult, max_score: i8) -> Html {
     let (f1_stats, f2_stats) = (
         result.stats.option_scores.get(&head_to_head.finalist1).unwrap(),
         result.stats.option_scores.get(&head_to_head.finalist2).unwrap()
     );
     
     let (f1_nonzero, f2_nonzero) = (
-        (1..=5).map(|i| f1_stats.frequency.get(&i).unwrap_or(&0)).sum::<usize>(),
-        (1..=5).map(|i| f2_stats.frequency.get(&i).unwrap_or(&0)).sum::<usize>()
+        (1..=max_score).map(|i| f1_stats.frequency.get(&i).unwrap_or(&0)).sum::<usize>(),
+        (1..=max_score).map(|i| f2_stats.frequency.get(&i).unwrap_or(&0)).sum::<usize>()
     );
  
     html! {
@@ -359,18 +669,67 @@ fn render_winner_section(winner: &str, head_to_head: &HeadToHeadResult, result:
             </div>
             <div class={SPACE_Y_BASE}>
                 {render_head_to_head_results(head_to_head)}
-                {render_winner_details(winner, head_to_head, is_tie, (&head_to_head.finalist1, f1_nonzero, f1_stats),
+                {render_runner_up_matchups(&result.runner_up_matchups)}
+                {result.margin.as_ref().map_or(html! {}, |margin| render_margin(margin, winner, head_to_head))}
+                {render_winner_details(winner, head_to_head, result.tiebreak.as_ref(), max_score, (&head_to_head.finalist1, f1_nonzero, f1_stats),
                                      (&head_to_head.finalist2, f2_nonzero, f2_stats))}
             </div>
         </div>
     }
 }
 
-fn render_error_section(error_msg: &str) -> Html {
+fn render_elected_seats(rounds: &[WinnerRound<String>]) -> Html {
+    html! {
+        <div class={combine_classes(STATS_CARD, STATS_CARD_SUCCESS)}>
+            <h3 class={HEADING_SM}>{format!("🏆 {} Seats Elected", rounds.len())}</h3>
+            <div class={SPACE_Y_BASE}>
+                {for rounds.iter().enumerate().map(|(i, round)| html! {
+                    <div class="bg-gray-800/50 rounded-lg p-4">
+                        <div class="relative mb-1">
+                            <div class="max-w-full break-words pr-16 font-medium">{&round.winner}</div>
+                            <div class="absolute right-0 top-0 text-sm text-gray-400">{format!("Seat {}", i + 1)}</div>
+                        </div>
+                        <div class="text-sm text-gray-400">{format_seat_round(round)}</div>
+                        {render_tiebreak_stage(&round.tiebreak, TiebreakStage::FirstFinalist)}
+                    </div>
+                })}
+            </div>
+        </div>
+    }
+}
+
+fn format_seat_round(round: &WinnerRound<String>) -> String {
+    match &round.runoff {
+        Some(runoff) => {
+            let (winner_votes, loser_votes, opponent) = if runoff.winner == runoff.finalist1 {
+                (runoff.head_to_head.0, runoff.head_to_head.1, &runoff.finalist2)
+            } else {
+                (runoff.head_to_head.1, runoff.head_to_head.0, &runoff.finalist1)
+            };
+            format!("Won the runoff against {} by {} – {}", opponent, winner_votes, loser_votes)
+        }
+        None if round.scores.len() == 1 => "Elected as the last remaining option".to_string(),
+        None => {
+            let score = round.scores.iter()
+                .find(|(option, _)| *option == round.winner)
+                .map_or(0.0, |(_, score)| *score);
+            format!("Weighted score {:.2}, ballot weight remaining {:.2}", score, round.weight_remaining)
+        }
+    }
+}
+
+fn render_outcome_section(outcome: &TabulationOutcome<String>) -> Html {
+    let message = match outcome {
+        TabulationOutcome::FirstPlaceTie { tied } => format!("Perfect tie for first between {}", tied.join(", ")),
+        TabulationOutcome::SecondPlaceTie { tied } => format!("Tie for second place between {}", tied.join(", ")),
+        TabulationOutcome::InsufficientBallots => "No ballots were cast".to_string(),
+        TabulationOutcome::InsufficientOptions => "Not enough options to elect a winner".to_string(),
+        TabulationOutcome::Winner => return html! {},
+    };
     html! {
         <div class={combine_classes(STATS_CARD, STATS_CARD_WARNING)}>
             <h3 class={HEADING_SM}>{"Unable to Determine Winner"}</h3>
-            <p>{error_msg}</p>
+            <p>{message}</p>
         </div>
     }
 }
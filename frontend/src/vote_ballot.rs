@@ -2,7 +2,8 @@ use yew::prelude::*;
 use gloo_net::http::Request;
 use std::collections::HashMap;
 use serde::Serialize;
-use shared::models::{Vote, BallotResponse};
+use shared::models::{Vote, BallotResponse, ManageVoteRequest, ResultsVisibility};
+use shared::star_logic::BlankPolicy;
 use yew_router::prelude::*;
 use web_sys::window;
 use gloo_timers::callback::Timeout;
@@ -11,14 +12,16 @@ use crate::Route;
 use crate::styles::*;
 use crate::hcaptcha::HCaptcha;
 use crate::config::CONFIG;
+use crate::management;
 
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 struct BallotRequest {
     csrf_token: String,
     captcha_token: String,
-    scores: HashMap<String, i8>,
+    scores: HashMap<String, Option<i8>>,
     user_fingerprint: String,
+    credential: Option<String>,
 }
 
 #[derive(PartialEq)]
@@ -36,18 +39,22 @@ pub struct Props {
 }
 
 pub enum Msg {
-    UpdateScore(String, i8),
+    UpdateScore(String, Option<i8>),
+    UpdateCredential(String),
     Submit,
     SubmissionComplete(Result<BallotResponse, String>),
     CaptchaVerified(String),
     CaptchaExpired,
     CaptchaError,
+    EndVote,
+    EndVoteComplete(Result<(), String>),
 }
 
 pub struct VoteBallot {
-    scores: HashMap<String, i8>,
+    scores: HashMap<String, Option<i8>>,
     state: SubmissionState,
     captcha_token: Option<String>,
+    credential: String,
 }
 
 impl Component for VoteBallot {
@@ -57,10 +64,11 @@ impl Component for VoteBallot {
     fn create(ctx: &Context<Self>) -> Self {
         Self {
             scores: ctx.props().vote.options.iter()
-                .map(|opt| (opt.clone(), 0))
+                .map(|opt| (opt.clone(), None))
                 .collect(),
             state: SubmissionState::Ready,
             captcha_token: None,
+            credential: String::new(),
         }
     }
 
@@ -74,6 +82,10 @@ impl Component for VoteBallot {
                     false
                 }
             }
+            Msg::UpdateCredential(credential) => {
+                self.credential = credential;
+                false
+            }
             Msg::Submit => {
                 if matches!(self.state, SubmissionState::Submitting) {
                     return false;
@@ -94,10 +106,14 @@ impl Component for VoteBallot {
                     captcha_token,
                     scores,
                     user_fingerprint: shared::user_info::generate_browser_fingerprint(),
+                    credential: ctx.props().vote.weighted.then(|| self.credential.trim().to_string()),
                 };
                 
                 ctx.link().send_future(async move {
-                    let req = match Request::post(&format!("{}/vote/{}/ballot", CONFIG.api_base_url, vote_id))
+                    let req = match Request::post(&format!(
+                        "{}/vote/{}/ballot{}",
+                        CONFIG.api_base_url, vote_id, management::access_query(&vote_id.to_string())
+                    ))
                         .json(&request)
                         .map_err(|e| e.to_string()) {
                         Ok(req) => req,
@@ -115,7 +131,13 @@ impl Component for VoteBallot {
                             }
                         },
                         429 => Msg::SubmissionComplete(Err("You're voting too quickly. Please try again.".into())),
-                        403 => Msg::SubmissionComplete(Err("Action not allowed: The voting period may have ended, or you may have already cast your ballot.".into())),
+                        403 => Msg::SubmissionComplete(Err("Action not allowed: The voting period may have ended, your voter credential may be invalid or used, or you may have already cast your ballot.".into())),
+                        400 => {
+                            let error = response.json::<shared::error::ErrorResponse>().await
+                                .map(|err| err.error)
+                                .unwrap_or_else(|_| "Invalid ballot.".into());
+                            Msg::SubmissionComplete(Err(error))
+                        },
                         _ => Msg::SubmissionComplete(Err("Failed to submit ballot.".into()))
                     }
                 });
@@ -170,18 +192,65 @@ impl Component for VoteBallot {
                 self.state = SubmissionState::Error("Captcha verification failed".into());
                 true
             }
+            Msg::EndVote => {
+                let vote_id = ctx.props().vote.id;
+                let Some(management_token) = management::token(&vote_id.to_string()) else { return false };
+                let request = ManageVoteRequest { csrf_token: ctx.props().csrf_token.clone(), management_token };
+
+                ctx.link().send_future(async move {
+                    let response = match Request::post(&format!("{}/vote/{}/end", CONFIG.api_base_url, vote_id))
+                        .json(&request)
+                        .map_err(|e| e.to_string()) {
+                        Ok(req) => req.send().await.map_err(|e| e.to_string()),
+                        Err(e) => Err(e),
+                    };
+                    match response {
+                        Ok(response) if response.ok() => Msg::EndVoteComplete(Ok(())),
+                        Ok(response) => {
+                            let error = response.json::<shared::error::ErrorResponse>().await
+                                .map(|err| err.error)
+                                .unwrap_or_else(|_| "Failed to end vote.".into());
+                            Msg::EndVoteComplete(Err(error))
+                        }
+                        Err(e) => Msg::EndVoteComplete(Err(e)),
+                    }
+                });
+                false
+            }
+            Msg::EndVoteComplete(result) => {
+                match result {
+                    Ok(()) => {
+                        if let Some(navigator) = ctx.link().navigator() {
+                            navigator.push(&Route::Results { id: ctx.props().vote.id.to_string() });
+                        }
+                        false
+                    }
+                    Err(error) => {
+                        self.state = SubmissionState::Error(error);
+                        true
+                    }
+                }
+            }
         }
     }
 
     fn view(&self, ctx: &Context<Self>) -> Html {
         html! {
             <div class="space-y-6">
+                <p class={TEXT_MUTED}>
+                    {match ctx.props().vote.blank_policy {
+                        BlankPolicy::CountAsZero => "Options you leave at \u{2013} (no opinion) count as 0.",
+                        BlankPolicy::Exclude => "Options you leave at \u{2013} (no opinion) are left out of their average and head-to-head counts.",
+                    }}
+                </p>
                 <div class="space-y-4">
                     {for ctx.props().vote.options.iter().map(|option| {
                         self.render_option(ctx, option)
                     })}
                 </div>
 
+                {self.render_credential_input(ctx)}
+
                 <div class="mb-4">
                     <HCaptcha
                         site_key="ce22ff56-8b34-4c5c-8a2c-225ad14caba0"
@@ -202,7 +271,8 @@ impl Component for VoteBallot {
 
 impl VoteBallot {
     fn render_option(&self, ctx: &Context<Self>, option: &str) -> Html {
-        let current_score = *self.scores.get(option).unwrap_or(&0);
+        let current_score = self.scores.get(option).copied().flatten();
+        let max_score = i8::try_from(ctx.props().vote.max_score).unwrap_or(5);
         let is_submitting = matches!(self.state, SubmissionState::Submitting);
 
         html! {
@@ -212,17 +282,17 @@ impl VoteBallot {
                 </div>
                 <div class="flex flex-col space-y-2 sm:flex-row sm:space-y-0 sm:space-x-4 sm:items-center">
                     <div class="flex items-center justify-center w-16 h-16 rounded-lg bg-gray-700 text-4xl font-bold text-center text-gray-300 border border-gray-500 shadow-md">
-                        {current_score}
+                        {current_score.map_or_else(|| "\u{2013}".to_string(), |score| score.to_string())}
                     </div>
-                    <div class="grid grid-cols-6 gap-2 w-full sm:flex sm:w-auto">
-                        {(0..=5).map(|score| {
+                    <div class="grid grid-cols-6 gap-2 w-full sm:flex sm:flex-wrap sm:w-auto">
+                        {std::iter::once(None).chain((0..=max_score).map(Some)).map(|score| {
                             let opt = option.to_string();
                             let onclick = ctx.link().callback(move |_| {
                                 Msg::UpdateScore(opt.clone(), score)
                             });
 
                             let button_classes = if current_score == score {
-                                if score == 0 {
+                                if score.unwrap_or(0) == 0 {
                                     "w-12 h-12 rounded-full flex items-center justify-center transition-all duration-150 ease-out bg-gray-600 text-gray-300 text-lg shadow ring-2 ring-white"
                                 } else {
                                     "w-12 h-12 rounded-full flex items-center justify-center transform scale-105 transition-all duration-150 ease-out bg-blue-500 text-white text-lg shadow-lg ring-2 ring-blue-400 ring-offset-1 ring-offset-gray-800"
@@ -237,8 +307,9 @@ impl VoteBallot {
                                     disabled={is_submitting}
                                     onclick={onclick}
                                     class={button_classes}
+                                    title={if score.is_none() { "No opinion" } else { "" }}
                                 >
-                                    {score}
+                                    {score.map_or_else(|| "\u{2013}".to_string(), |score| score.to_string())}
                                 </button>
                             }
                         }).collect::<Html>()}
@@ -248,6 +319,26 @@ impl VoteBallot {
         }
     }
 
+    fn render_credential_input(&self, ctx: &Context<Self>) -> Html {
+        if !ctx.props().vote.weighted {
+            return html! {};
+        }
+
+        let oninput = ctx.link().callback(|e: InputEvent| {
+            let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+            Msg::UpdateCredential(input.value())
+        });
+
+        html! {
+            <div class={SPACE_Y_BASE}>
+                <label class={TEXT_LABEL}>{"Voter Credential"}</label>
+                <input type="text" class={INPUT_BASE} {oninput}
+                    placeholder="Paste the credential issued by the vote organizer" />
+                <p class={TEXT_MUTED}>{"This vote is weighted; your ballot counts with the weight attached to your credential."}</p>
+            </div>
+        }
+    }
+
     fn render_submission_controls(&self, ctx: &Context<Self>) -> Html {
         match &self.state {
             SubmissionState::Ready | SubmissionState::Error(_) => html! {
@@ -267,6 +358,21 @@ impl VoteBallot {
                         classes="flex-1 bg-gray-600 hover:bg-gray-700 text-white px-8 py-4 rounded-lg text-lg font-semibold shadow-lg text-center transform transition-all duration-150 hover:scale-105">
                         {"Cancel"}
                     </Link<Route>>
+                    if management::token(&ctx.props().vote.id.to_string()).is_some() {
+                        <button
+                            type="button"
+                            onclick={ctx.link().callback(|_| Msg::EndVote)}
+                            class="flex-1 bg-red-600 hover:bg-red-700 text-white px-8 py-4 rounded-lg text-lg font-semibold shadow-lg transform transition-all duration-150 hover:scale-105"
+                        >
+                            {"End Vote Early"}
+                        </button>
+                    }
+                    if ctx.props().vote.results_visibility != ResultsVisibility::HiddenUntilClose {
+                        <Link<Route> to={Route::Results { id: ctx.props().vote.id.to_string() }}
+                            classes="flex-1 bg-gray-600 hover:bg-gray-700 text-white px-8 py-4 rounded-lg text-lg font-semibold shadow-lg text-center transform transition-all duration-150 hover:scale-105">
+                            {"Live Results"}
+                        </Link<Route>>
+                    }
                 </div>
             },
             SubmissionState::Submitting => html! {
@@ -3,18 +3,20 @@ use web_sys::{HtmlInputElement, HtmlSelectElement};
 use yew::prelude::*;
 use yew_router::prelude::*;
 use wasm_bindgen::JsValue;
-use crate::{vote_option_manager::VoteOptionManager, styles::*, Route, hcaptcha::HCaptcha};
-use shared::{models::*, error::ErrorResponse, user_info::generate_browser_fingerprint};
+use crate::{vote_option_manager::VoteOptionManager, styles::*, Route, hcaptcha::HCaptcha, management};
+use shared::{models::*, error::ErrorResponse, user_info::generate_browser_fingerprint, star_logic::{BlankPolicy, MultiWinnerMethod}};
 use std::future::Future;
 use std::pin::Pin;
 use gloo_timers::callback::Timeout;
 use crate::config::CONFIG;
+use time::OffsetDateTime;
 
 const MAX_TITLE_LENGTH: usize = 100;
 const MAX_DESCRIPTION_LENGTH: usize = 500;
 const MAX_OPTION_LENGTH: usize = 40;
 const MAX_OPTIONS: usize = 20;
 const MAX_DURATION_DAYS: i64 = 6;
+const MAX_SCORE: i64 = 10;
 
 #[derive(Clone)]
 pub struct FormState {
@@ -24,6 +26,14 @@ pub struct FormState {
     days: i32,
     hours: i32, 
     minutes: i32,
+    seats: i32,
+    winner_method: MultiWinnerMethod,
+    max_score: i32,
+    weighted: bool,
+    blank_policy: BlankPolicy,
+    results_visibility: ResultsVisibility,
+    opens_at: Option<OffsetDateTime>,
+    visibility: VoteVisibility,
     csrf_token: Option<String>,
     captcha_token: Option<String>,
     error: Option<String>,
@@ -39,6 +49,14 @@ impl Default for FormState {
             days: 0,
             hours: 0,
             minutes: 0,
+            seats: 1,
+            winner_method: MultiWinnerMethod::default(),
+            max_score: 5,
+            weighted: false,
+            blank_policy: BlankPolicy::default(),
+            results_visibility: ResultsVisibility::default(),
+            opens_at: None,
+            visibility: VoteVisibility::default(),
             csrf_token: None,
             captcha_token: None,
             error: None,
@@ -57,7 +75,7 @@ pub enum Msg {
     UpdateOptions(Vec<String>),
     TokenReceived(String),
     Submit,
-    SubmitResult(Result<Vote, String>),
+    SubmitResult(Result<CreateVoteResponse, String>),
     CaptchaVerified(String),
     CaptchaExpired,
     CaptchaError,
@@ -99,6 +117,14 @@ impl Component for VoteCreate {
                     "days" => self.state.days = value.parse().unwrap_or(0),
                     "hours" => self.state.hours = value.parse().unwrap_or(0),
                     "minutes" => self.state.minutes = value.parse().unwrap_or(0),
+                    "seats" => self.state.seats = value.parse().unwrap_or(1),
+                    "winner_method" => self.state.winner_method = value.parse().unwrap_or_default(),
+                    "max_score" => self.state.max_score = value.parse().unwrap_or(5),
+                    "weighted" => self.state.weighted = value == "true",
+                    "blank_policy" => self.state.blank_policy = value.parse().unwrap_or_default(),
+                    "results_visibility" => self.state.results_visibility = value.parse().unwrap_or_default(),
+                    "opens_at" => self.state.opens_at = parse_local_datetime(&value),
+                    "visibility" => self.state.visibility = value.parse().unwrap_or_default(),
                     _ => return false,
                 }
                 true
@@ -132,8 +158,17 @@ impl Component for VoteCreate {
             },
             Msg::SubmitResult(result) => {
                 match result {
-                    Ok(vote) => {
-                        self.navigator.push(&Route::Vote { id: vote.id.to_string() });
+                    Ok(CreateVoteResponse { vote, management_token, access_code }) => {
+                        management::save_token(vote.id, &management_token);
+                        let route = Route::Vote { id: vote.id.to_string() };
+                        match access_code {
+                            // Keep the code in the address bar so the link can be shared as is.
+                            Some(access_code) => {
+                                management::save_access_code(&vote.id.to_string(), &access_code);
+                                let _ = self.navigator.push_with_query(&route, &[("access_code", access_code)]);
+                            }
+                            None => self.navigator.push(&route),
+                        }
                         false
                     }
                     Err(error) => {
@@ -218,6 +253,13 @@ impl VoteCreate {
         if self.state.options.len() > MAX_OPTIONS {
             return Err(format!("Maximum {} options allowed", MAX_OPTIONS));
         }
+        if self.state.seats < 1 || self.state.seats as usize > self.state.options.len() {
+            return Err("Seats cannot exceed the number of options".into());
+        }
+
+        if self.state.opens_at.is_some_and(|opens_at| opens_at <= OffsetDateTime::now_utc()) {
+            return Err("Opening time must be in the future".into());
+        }
 
         let total_hours = (self.state.days * 24) + self.state.hours;
         if total_hours == 0 && self.state.minutes == 0 {
@@ -240,6 +282,14 @@ impl VoteCreate {
             duration_hours: (self.state.days * 24) + self.state.hours,
             duration_minutes: self.state.minutes,
             user_fingerprint: generate_browser_fingerprint(),
+            seats: self.state.seats,
+            winner_method: self.state.winner_method,
+            max_score: self.state.max_score,
+            weighted: self.state.weighted,
+            blank_policy: self.state.blank_policy,
+            results_visibility: self.state.results_visibility,
+            opens_at: self.state.opens_at,
+            visibility: self.state.visibility,
         }
     }
 
@@ -271,6 +321,14 @@ impl VoteCreate {
                 {self.render_textarea(ctx, "description", "Description", MAX_DESCRIPTION_LENGTH)}
                 {self.render_duration(ctx)}
                 {self.render_options(ctx)}
+                {self.render_seats(ctx)}
+                <div class={GRID_COLS_3}>
+                    {self.render_select(ctx, "max_score", "Highest Score", 1..=MAX_SCORE)}
+                    {self.render_blank_policy(ctx)}
+                    {self.render_results_visibility(ctx)}
+                </div>
+                {self.render_weighted(ctx)}
+                {self.render_visibility(ctx)}
     
                 <div class="mb-4 mt-4">
                     <HCaptcha
@@ -355,6 +413,21 @@ impl VoteCreate {
                     {self.render_select(ctx, "hours", "Hours", 0..=23)}
                     {self.render_select(ctx, "minutes", "Minutes", 0..=59)}
                 </div>
+                {self.render_opens_at(ctx)}
+            </div>
+        }
+    }
+
+    fn render_opens_at(&self, ctx: &Context<Self>) -> Html {
+        let onchange = ctx.link().callback(|e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            Msg::UpdateField("opens_at".into(), input.value())
+        });
+
+        html! {
+            <div>
+                <label class={TEXT_LABEL_SM}>{"Opens At (leave empty to open now)"}</label>
+                <input type="datetime-local" class={INPUT_BASE} {onchange} />
             </div>
         }
     }
@@ -365,6 +438,8 @@ impl VoteCreate {
             "days" => self.state.days,
             "hours" => self.state.hours,
             "minutes" => self.state.minutes,
+            "seats" => self.state.seats,
+            "max_score" => self.state.max_score,
             _ => return html! {},
         };
         let onchange = ctx.link().callback(move |e: Event| {
@@ -385,6 +460,116 @@ impl VoteCreate {
         }
     }
 
+    fn render_seats(&self, ctx: &Context<Self>) -> Html {
+        let max_seats = self.state.options.len().max(1) as i64;
+        let onchange = ctx.link().callback(|e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            Msg::UpdateField("winner_method".into(), select.value())
+        });
+        let method_option = |method: MultiWinnerMethod, label: &str| html! {
+            <option value={method.as_str()} selected={self.state.winner_method == method}>{label}</option>
+        };
+
+        html! {
+            <div class={SPACE_Y_BASE}>
+                <label class={TEXT_LABEL}>{"Winners"}</label>
+                <div class={GRID_COLS_3}>
+                    {self.render_select(ctx, "seats", "Seats", 1..=max_seats)}
+                    if self.state.seats > 1 {
+                        <div>
+                            <label class={TEXT_LABEL_SM}>{"Method"}</label>
+                            <select class={INPUT_BASE} {onchange}>
+                                {method_option(MultiWinnerMethod::BlocStar, "Bloc STAR")}
+                                {method_option(MultiWinnerMethod::AllocatedScore, "Proportional STAR (Allocated Score)")}
+                            </select>
+                        </div>
+                    }
+                </div>
+            </div>
+        }
+    }
+
+    fn render_blank_policy(&self, ctx: &Context<Self>) -> Html {
+        let onchange = ctx.link().callback(|e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            Msg::UpdateField("blank_policy".into(), select.value())
+        });
+        let policy_option = |policy: BlankPolicy, label: &str| html! {
+            <option value={policy.as_str()} selected={self.state.blank_policy == policy}>{label}</option>
+        };
+
+        html! {
+            <div>
+                <label class={TEXT_LABEL_SM}>{"No-opinion Scores"}</label>
+                <select class={INPUT_BASE} {onchange}>
+                    {policy_option(BlankPolicy::CountAsZero, "Count as 0")}
+                    {policy_option(BlankPolicy::Exclude, "Leave out")}
+                </select>
+            </div>
+        }
+    }
+
+    fn render_results_visibility(&self, ctx: &Context<Self>) -> Html {
+        let onchange = ctx.link().callback(|e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            Msg::UpdateField("results_visibility".into(), select.value())
+        });
+        let visibility_option = |visibility: ResultsVisibility, label: &str| html! {
+            <option value={visibility.as_str()} selected={self.state.results_visibility == visibility}>{label}</option>
+        };
+
+        html! {
+            <div>
+                <label class={TEXT_LABEL_SM}>{"Results Before Close"}</label>
+                <select class={INPUT_BASE} {onchange}>
+                    {visibility_option(ResultsVisibility::HiddenUntilClose, "Hidden")}
+                    {visibility_option(ResultsVisibility::Live, "Live to everyone")}
+                    {visibility_option(ResultsVisibility::CreatorOnly, "Live to me only")}
+                </select>
+            </div>
+        }
+    }
+
+    fn render_visibility(&self, ctx: &Context<Self>) -> Html {
+        let onchange = ctx.link().callback(|e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            Msg::UpdateField("visibility".into(), select.value())
+        });
+        let visibility_option = |visibility: VoteVisibility, label: &str| html! {
+            <option value={visibility.as_str()} selected={self.state.visibility == visibility}>{label}</option>
+        };
+
+        html! {
+            <div class={SPACE_Y_BASE}>
+                <label class={TEXT_LABEL_SM}>{"Who Can Find This Vote"}</label>
+                <select class={INPUT_BASE} {onchange}>
+                    {visibility_option(VoteVisibility::Public, "Public: listed on the home page")}
+                    {visibility_option(VoteVisibility::Unlisted, "Unlisted: anyone with the link")}
+                    {visibility_option(VoteVisibility::Private, "Private: link plus access code")}
+                </select>
+                if self.state.visibility == VoteVisibility::Private {
+                    <p class={TEXT_MUTED}>{"An access code is generated for you and added to the vote's link; share that link with your voters."}</p>
+                }
+            </div>
+        }
+    }
+
+    fn render_weighted(&self, ctx: &Context<Self>) -> Html {
+        let onchange = ctx.link().callback(|e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            Msg::UpdateField("weighted".into(), input.checked().to_string())
+        });
+
+        html! {
+            <div class={SPACE_Y_BASE}>
+                <label class={TEXT_LABEL}>
+                    <input type="checkbox" class="mr-2" checked={self.state.weighted} {onchange} />
+                    {"Weighted ballots (voters need a credential you issue)"}
+                </label>
+            </div>
+        }
+    }
+
     fn render_options(&self, ctx: &Context<Self>) -> Html {
         html! {
             <div class={SPACE_Y_BASE}>
@@ -406,7 +591,19 @@ impl VoteCreate {
     }
 }
 
-fn submit_vote(request: CreateVoteRequest) -> Pin<Box<dyn Future<Output = Result<Vote, String>> + 'static>> {
+/// Reads a `datetime-local` value, which is in the browser's time zone.
+fn parse_local_datetime(value: &str) -> Option<OffsetDateTime> {
+    if value.is_empty() {
+        return None;
+    }
+    let millis = js_sys::Date::new(&JsValue::from_str(value)).get_time();
+    if millis.is_nan() {
+        return None;
+    }
+    OffsetDateTime::from_unix_timestamp_nanos(millis as i128 * 1_000_000).ok()
+}
+
+fn submit_vote(request: CreateVoteRequest) -> Pin<Box<dyn Future<Output = Result<CreateVoteResponse, String>> + 'static>> {
     Box::pin(async move {
         let response = Request::post(&format!("{}/vote", CONFIG.api_base_url))
             .json(&request)
@@ -416,7 +613,7 @@ fn submit_vote(request: CreateVoteRequest) -> Pin<Box<dyn Future<Output = Result
             .map_err(|e| e.to_string())?;
 
         match response.status() {
-            200 => response.json::<Vote>().await.map_err(|e| e.to_string()),
+            200 => response.json::<CreateVoteResponse>().await.map_err(|e| e.to_string()),
             429 => Err("Please wait an hour before creating another vote".into()),
             400 => {
                 let error = response.json::<ErrorResponse>().await
@@ -5,6 +5,8 @@ use yew_router::prelude::*;
 use crate::Route;
 use crate::styles::*;
 use crate::config::CONFIG;
+use crate::management;
+use serde::Deserialize;
 use time::{OffsetDateTime, Duration};
 use std::rc::Rc;
 use futures::try_join;
@@ -15,20 +17,31 @@ pub struct Props {
     pub id: String,
 }
 
+/// Links to private votes may carry their access code.
+#[derive(Deserialize)]
+struct AccessQuery {
+    access_code: Option<String>,
+}
+
 #[derive(Clone)]
 enum State {
     Loading,
     Active { vote: Rc<Vote>, csrf_token: String, time_remaining: Duration },
+    /// A private vote; `rejected` once a code has been tried and refused.
+    AccessCodeRequired { rejected: bool },
     Error(String),
     NotFound,
 }
 
 pub struct VoteDisplay {
     state: State,
+    access_code_input: NodeRef,
 }
 
 pub enum Msg {
     DataReceived(Vote, String),
+    AccessDenied,
+    SubmitAccessCode,
     Error(String),
 }
 
@@ -38,15 +51,15 @@ impl Component for VoteDisplay {
 
     fn create(ctx: &Context<Self>) -> Self {
         let id = ctx.props().id.clone();
-        
-        ctx.link().send_future(async move {
-            match fetch_vote_data(&id).await {
-                Ok((vote, token)) => Msg::DataReceived(vote, token),
-                Err(e) => Msg::Error(e),
-            }
-        });
+        let shared_code = ctx.link().location()
+            .and_then(|location| location.query::<AccessQuery>().ok())
+            .and_then(|query| query.access_code);
+        if let Some(access_code) = shared_code {
+            management::save_access_code(&id, &access_code);
+        }
+        Self::load(ctx);
 
-        Self { state: State::Loading }
+        Self { state: State::Loading, access_code_input: NodeRef::default() }
     }
 
     fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
@@ -70,6 +83,21 @@ impl Component for VoteDisplay {
                     true
                 }
             }
+            Msg::AccessDenied => {
+                let rejected = management::access_code(&ctx.props().id).is_some();
+                self.state = State::AccessCodeRequired { rejected };
+                true
+            }
+            Msg::SubmitAccessCode => {
+                let Some(input) = self.access_code_input.cast::<web_sys::HtmlInputElement>() else { return false };
+                let access_code = input.value().trim().to_string();
+                if access_code.is_empty() {
+                    return false;
+                }
+                management::save_access_code(&ctx.props().id, &access_code);
+                Self::load(ctx);
+                false
+            }
             Msg::Error(error) => {
                 self.state = if error.contains("not found") {
                     State::NotFound
@@ -81,28 +109,73 @@ impl Component for VoteDisplay {
         }
     }
 
-    fn view(&self, _: &Context<Self>) -> Html {
+    fn view(&self, ctx: &Context<Self>) -> Html {
         match &self.state {
             State::Loading => render_loading(),
             State::Active { vote, csrf_token, time_remaining } => render_active(vote, csrf_token, *time_remaining),
+            State::AccessCodeRequired { rejected } => self.render_access_code_form(ctx, *rejected),
             State::Error(error) => render_error(error),
             State::NotFound => render_not_found(),
         }
     }
 }
 
-async fn fetch_vote_data(id: &str) -> Result<(Vote, String), String> {
+impl VoteDisplay {
+    fn load(ctx: &Context<Self>) {
+        let id = ctx.props().id.clone();
+        ctx.link().send_future(async move {
+            match fetch_vote_data(&id).await {
+                Ok(Some((vote, token))) => Msg::DataReceived(vote, token),
+                Ok(None) => Msg::AccessDenied,
+                Err(e) => Msg::Error(e),
+            }
+        });
+    }
+
+    fn render_access_code_form(&self, ctx: &Context<Self>, rejected: bool) -> Html {
+        let onsubmit = ctx.link().callback(|e: SubmitEvent| {
+            e.prevent_default();
+            Msg::SubmitAccessCode
+        });
+
+        html! {
+            <div class="container mx-auto px-4 py-8 max-w-md">
+                <form {onsubmit} class="bg-gray-800 rounded-lg shadow-xl p-6 text-white space-y-4">
+                    <h1 class="text-xl font-bold text-gray-100">{"Private Vote"}</h1>
+                    <p class="text-gray-300">{"Enter the access code you were given to view this vote."}</p>
+                    if rejected {
+                        <div class={alert_style("error")}>{"That access code is not valid"}</div>
+                    }
+                    <input
+                        ref={self.access_code_input.clone()}
+                        type="text"
+                        autocomplete="off"
+                        placeholder="Access code"
+                        class={INPUT_BASE}
+                    />
+                    <button type="submit" class={button_primary(false)}>{"Continue"}</button>
+                </form>
+            </div>
+        }
+    }
+}
+
+/// `None` if the vote is private and no valid access code is saved.
+async fn fetch_vote_data(id: &str) -> Result<Option<(Vote, String)>, String> {
     let (vote_resp, token_resp) = try_join!(
-        Request::get(&format!("{}/vote/{}", CONFIG.api_base_url, id)).send(),
+        Request::get(&format!("{}/vote/{}{}", CONFIG.api_base_url, id, management::access_query(id))).send(),
         Request::get(&format!("{}/csrf-token", CONFIG.api_base_url)).send()
     ).map_err(|e| e.to_string())?;
 
+    if vote_resp.status() == 403 {
+        return Ok(None);
+    }
     let vote = vote_resp.json::<Vote>().await
         .map_err(|_| "Failed to parse vote data".to_string())?;
     let token = token_resp.text().await
         .map_err(|_| "Failed to get CSRF token".to_string())?;
 
-    Ok((vote, token))
+    Ok(Some((vote, token)))
 }
 
 fn render_loading() -> Html {
@@ -136,15 +209,28 @@ fn render_active(vote: &Vote, csrf_token: &str, time_remaining: Duration) -> Htm
                 <h1 class="text-2xl font-bold mb-4 break-words text-gray-100">{&vote.title}</h1>
                 <p class="mb-6 text-lg text-gray-300 break-words whitespace-pre-wrap">{&vote.description}</p>
                 
-                <div class="bg-gray-700/50 p-4 rounded-lg mb-6">
-                    <h2 class="font-semibold mb-2">{"Time Remaining"}</h2>
-                    <p class="text-lg">{render_time_text(time_remaining)}</p>
-                </div>
-
-                <div class="border-t border-gray-600 pt-4 mb-3">
-                    {render_instructions(vote)}
-                    <VoteBallot vote={vote.clone()} csrf_token={csrf_token.to_string()} />
-                </div>
+                {match vote.opens_at.filter(|_| !vote.has_opened()) {
+                    Some(opens_at) => html! {
+                        <div class="bg-gray-700/50 p-4 rounded-lg mb-6">
+                            <h2 class="font-semibold mb-2">{"Voting Opens In"}</h2>
+                            <p class="text-lg">{render_duration_text(opens_at - OffsetDateTime::now_utc())}</p>
+                            <p class="text-sm text-gray-300 mt-1">{"Come back then to cast your ballot."}</p>
+                        </div>
+                    },
+                    None => html! {
+                        <>
+                            <div class="bg-gray-700/50 p-4 rounded-lg mb-6">
+                                <h2 class="font-semibold mb-2">{"Time Remaining"}</h2>
+                                <p class="text-lg">{render_time_text(time_remaining)}</p>
+                            </div>
+
+                            <div class="border-t border-gray-600 pt-4 mb-3">
+                                {render_instructions(vote)}
+                                <VoteBallot vote={vote.clone()} csrf_token={csrf_token.to_string()} />
+                            </div>
+                        </>
+                    },
+                }}
             </div>
         </div>
     }
@@ -165,11 +251,15 @@ fn render_instructions(vote: &Vote) -> Html {
 }
 
 fn render_time_text(d: Duration) -> String {
+    format!("{} remaining", render_duration_text(d))
+}
+
+fn render_duration_text(d: Duration) -> String {
     if d.whole_hours() > 0 {
-        format!("{}h {}m remaining", d.whole_hours(), d.whole_minutes() % 60)
+        format!("{}h {}m", d.whole_hours(), d.whole_minutes() % 60)
     } else if d.whole_minutes() > 0 {
-        format!("{}m {}s remaining", d.whole_minutes(), d.whole_seconds() % 60)
+        format!("{}m {}s", d.whole_minutes(), d.whole_seconds() % 60)
     } else {
-        format!("{}s remaining", d.whole_seconds())
+        format!("{}s", d.whole_seconds())
     }
 }
\ No newline at end of file
@@ -1,6 +1,8 @@
 use yew::prelude::*;
 use gloo_net::http::Request;
 use shared::models::*;
+use shared::star_logic::PairwiseAnalysis;
+use shared::analytics::BallotAnalytics;
 use yew_router::prelude::*;
 use crate::Route;
 use crate::styles::*;
@@ -9,17 +11,34 @@ use time::OffsetDateTime;
 use futures::try_join;
 use crate::render_results::render_results_view;
 use crate::config::CONFIG;
+use crate::management;
 
 #[derive(Properties, PartialEq)]
 pub struct Props {
     pub id: String,
 }
 
+type ResultsData = (
+    Vote,
+    VoteResult,
+    Option<PairwiseAnalysis<String>>,
+    Option<Vec<MethodComparison>>,
+    Option<BallotAnalytics<String>>,
+    Option<BallotPage>,
+);
+
 #[derive(Default)]
 enum State {
     #[default]
     Loading,
-    Ready { vote: Rc<Vote>, result: Rc<VoteResult> },
+    Ready {
+        vote: Rc<Vote>,
+        result: Rc<VoteResult>,
+        pairwise: Option<Rc<PairwiseAnalysis<String>>>,
+        comparison: Option<Rc<Vec<MethodComparison>>>,
+        analytics: Option<Rc<BallotAnalytics<String>>>,
+        ballots: Option<Rc<BallotPage>>,
+    },
     Error(String),
 }
 
@@ -28,7 +47,7 @@ pub struct VoteResults {
 }
 
 impl Component for VoteResults {
-    type Message = Result<(Vote, VoteResult), String>;
+    type Message = Result<ResultsData, String>;
     type Properties = Props;
 
     fn create(ctx: &Context<Self>) -> Self {
@@ -37,11 +56,12 @@ impl Component for VoteResults {
 
         ctx.link().send_future(async move {
             match fetch_data(&id).await {
-                Ok((vote, _)) if OffsetDateTime::now_utc() <= vote.voting_ends_at => {
+                Ok(Some(data)) => Ok(data),
+                Ok(None) => {
                     navigator.push(&Route::Vote { id });
                     Err("Vote still active".into())
                 }
-                other => other
+                Err(e) => Err(e),
             }
         });
 
@@ -52,9 +72,13 @@ impl Component for VoteResults {
 
     fn update(&mut self, _: &Context<Self>, msg: Self::Message) -> bool {
         self.state = match msg {
-            Ok((vote, result)) => State::Ready { 
+            Ok((vote, result, pairwise, comparison, analytics, ballots)) => State::Ready { 
                 vote: Rc::new(vote), 
-                result: Rc::new(result) 
+                result: Rc::new(result),
+                pairwise: pairwise.map(Rc::new),
+                comparison: comparison.map(Rc::new),
+                analytics: analytics.map(Rc::new),
+                ballots: ballots.map(Rc::new),
             },
             Err(err) => State::Error(err),
         };
@@ -70,7 +94,16 @@ impl Component for VoteResults {
                     </div>
                 </div>
             },
-            State::Ready { vote, result } => render_results_view(vote, result),
+            State::Ready { vote, result, pairwise, comparison, analytics, ballots } => {
+                render_results_view(
+                    vote,
+                    result,
+                    pairwise.as_deref(),
+                    comparison.as_ref().map(|c| c.as_slice()),
+                    analytics.as_deref(),
+                    ballots.as_deref(),
+                )
+            }
             State::Error(err) => html! {
                 <div class={CONTAINER}>
                     <div class={alert_style("error")}>
@@ -85,16 +118,33 @@ impl Component for VoteResults {
     }
 }
 
-async fn fetch_data(id: &str) -> Result<(Vote, VoteResult), String> {
-    let (vote_resp, result_resp) = try_join!(
-        Request::get(&format!("{}/vote/{}", CONFIG.api_base_url, id)).send(),
-        Request::get(&format!("{}/vote/{}/result", CONFIG.api_base_url, id)).send()
+/// `None` if the vote is private and no valid access code is saved, or while
+/// the vote is open and its results are hidden from this user.
+async fn fetch_data(id: &str) -> Result<Option<ResultsData>, String> {
+    let query = management::access_query(id);
+    let (vote_resp, result_resp, pairwise_resp, comparison_resp, analytics_resp, ballots_resp) = try_join!(
+        Request::get(&format!("{}/vote/{}{}", CONFIG.api_base_url, id, query)).send(),
+        management::result_request(id).send(),
+        Request::get(&format!("{}/vote/{}/pairwise{}", CONFIG.api_base_url, id, query)).send(),
+        Request::get(&format!("{}/vote/{}/compare-methods{}", CONFIG.api_base_url, id, query)).send(),
+        Request::get(&format!("{}/vote/{}/analytics{}", CONFIG.api_base_url, id, query)).send(),
+        Request::get(&format!("{}/vote/{}/ballots{}", CONFIG.api_base_url, id, query)).send()
     ).map_err(|e| e.to_string())?;
 
+    if vote_resp.status() == 403 {
+        return Ok(None);
+    }
     let vote = vote_resp.json::<Vote>().await
         .map_err(|_| "Failed to parse vote data".to_string())?;
+    if result_resp.status() == 403 && OffsetDateTime::now_utc() <= vote.voting_ends_at {
+        return Ok(None);
+    }
     let result = result_resp.json::<VoteResult>().await
         .map_err(|_| "Failed to parse result data".to_string())?;
+    let pairwise = pairwise_resp.json::<PairwiseAnalysis<String>>().await.ok();
+    let comparison = comparison_resp.json::<Vec<MethodComparison>>().await.ok();
+    let analytics = analytics_resp.json::<BallotAnalytics<String>>().await.ok();
+    let ballots = ballots_resp.json::<BallotPage>().await.ok();
 
-    Ok((vote, result))
+    Ok(Some((vote, result, pairwise, comparison, analytics, ballots)))
 }
\ No newline at end of file
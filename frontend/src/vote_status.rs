@@ -1,9 +1,10 @@
 use yew::prelude::*;
 use gloo_net::http::Request;
-use shared::models::{Vote, VoteResult};
+use shared::models::{ManageVoteRequest, Vote, VoteResult};
 use yew_router::prelude::*;
 use crate::Route;
 use crate::config::CONFIG;
+use crate::management;
 use time::OffsetDateTime;
 use gloo_timers::callback::Interval;
 
@@ -50,7 +51,7 @@ pub fn vote_status(props: &Props) -> Html {
         let id = props.id.clone();
         use_effect_with_deps(move |_| {
             wasm_bindgen_futures::spawn_local(async move {
-                match Request::get(&format!("{}/vote/{}", CONFIG.api_base_url, id)).send().await {
+                match Request::get(&format!("{}/vote/{}{}", CONFIG.api_base_url, id, management::access_query(&id))).send().await {
                     Ok(response) => match response.json::<Option<Vote>>().await {
                         Ok(Some(vote)) => vote_state.set(Some(vote)),
                         Ok(None) => error_state.set(Some("Vote not found".into())),
@@ -59,7 +60,7 @@ pub fn vote_status(props: &Props) -> Html {
                     Err(e) => error_state.set(Some(e.to_string())),
                 }
 
-                if let Ok(response) = Request::get(&format!("{}/vote/{}/result", CONFIG.api_base_url, id)).send().await {
+                if let Ok(response) = management::result_request(&id).send().await {
                     if let Ok(result) = response.json::<VoteResult>().await {
                         result_state.set(Some(result));
                     }
@@ -78,11 +79,17 @@ pub fn vote_status(props: &Props) -> Html {
             let navigator = navigator.clone();
             
             wasm_bindgen_futures::spawn_local(async move {
+                let Some(management_token) = management::token(&id) else { return };
+                let Ok(csrf_token) = async {
+                    Request::get(&format!("{}/csrf-token", CONFIG.api_base_url)).send().await?.text().await
+                }.await else { return };
+
+                let request = ManageVoteRequest { csrf_token, management_token };
                 if let Ok(response) = Request::post(&format!("{}/vote/{}/end", CONFIG.api_base_url, id))
-                    .send()
-                    .await
+                    .json(&request)
+                    .map(|request| request.send())
                 {
-                    if response.status() == 200 {
+                    if response.await.is_ok_and(|response| response.ok()) {
                         navigator.push(&Route::Results { id });
                     }
                 }
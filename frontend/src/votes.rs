@@ -88,7 +88,8 @@ pub fn Votes() -> Html {
                 {state.votes.iter().map(|vote| {
                     let now = OffsetDateTime::now_utc();
                     let is_ended = now > vote.voting_ends_at;
-                    let time_remaining = if is_ended { Duration::ZERO } else { vote.voting_ends_at - now };
+                    let opens_in = vote.opens_at.filter(|_| !vote.has_opened()).map(|opens_at| opens_at - now);
+                    let time_remaining = if is_ended { Duration::ZERO } else { opens_in.unwrap_or(vote.voting_ends_at - now) };
                     let route = if is_ended {
                         Route::Results { id: vote.id.to_string() }
                     } else {
@@ -110,13 +111,14 @@ pub fn Votes() -> Html {
                                     {truncate(&vote.description, 20)}
                                 </p>
                                 <div class="mt-auto space-y-1">
-                                    <p class={TEXT_MUTED}>{"Ballots Cast: "}{vote.ballots.len()}</p>
+                                    <p class={TEXT_MUTED}>{"Ballots Cast: "}{vote.ballot_count}</p>
                                     <p class={TEXT_MUTED}>{"Options: "}{vote.options.len()}</p>
                                     <div class={FLEX_BETWEEN}>
                                         <div class={combine_classes("font-medium", 
                                             if is_ended { "text-orange-400" } else { MEGA_PULSE }
                                         )}>
                                             {if is_ended { "Vote Concluded" }
+                                             else if opens_in.is_some() { "Scheduled" }
                                              else if time_remaining.whole_hours() > 0 { "Active Vote" }
                                              else { "Ending Soon" }}
                                         </div>
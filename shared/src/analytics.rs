@@ -0,0 +1,148 @@
+use std::hash::Hash;
+use serde::{Deserialize, Serialize};
+use crate::star_logic::Election;
+
+pub const MAX_CLUSTERS: usize = 3;
+const CLUSTER_ITERATIONS: usize = 20;
+
+/// Ballot-level behaviour for a finished vote. Every share is weighted by
+/// ballot weight, so on unweighted votes it is a share of ballots.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BallotAnalytics<T> {
+    pub options: Vec<T>,
+    /// Pearson correlation between each pair of options over the ballots that
+    /// scored both; `None` when either option's scores never vary.
+    pub correlation: Vec<Vec<Option<f64>>>,
+    /// Ballots giving a nonzero score to exactly one option.
+    pub bullet_share: f64,
+    /// Ballots using only the bottom and top score, with at least one of each.
+    pub exaggerated_share: f64,
+    pub clusters: Vec<BallotCluster<T>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BallotCluster<T> {
+    pub share: f64,
+    pub centroid: Vec<(T, f64)>,
+}
+
+/// Blank scores count as 0 for clustering only; correlation skips them.
+pub fn analyze<T: Clone + Eq + Hash + Ord>(election: &Election<T>) -> BallotAnalytics<T> {
+    let options: Vec<T> = election.options().cloned().collect();
+    let n = options.len();
+    let max_score = election.max_score();
+
+    let ballots: Vec<(Vec<Option<i8>>, f64)> = election.ballot_groups()
+        .map(|(scores, weight)| {
+            let scores = (0..n).map(|i| scores.get(i).copied().flatten().map(|s| s.as_i8())).collect();
+            (scores, weight as f64)
+        })
+        .collect();
+    let total_weight: f64 = ballots.iter().map(|(_, w)| w).sum();
+    let share = |pred: &dyn Fn(&[Option<i8>]) -> bool| {
+        if total_weight == 0.0 {
+            return 0.0;
+        }
+        ballots.iter().filter(|(scores, _)| pred(scores)).map(|(_, w)| w).sum::<f64>() / total_weight
+    };
+
+    let bullet_share = share(&|scores| scores.iter().flatten().filter(|&&s| s > 0).count() == 1);
+    let exaggerated_share = share(&|scores| {
+        let scored: Vec<_> = scores.iter().flatten().collect();
+        scored.iter().all(|&&s| s == 0 || s == max_score)
+            && scored.contains(&&0)
+            && scored.contains(&&max_score)
+    });
+
+    let correlation = (0..n)
+        .map(|i| (0..n).map(|j| correlate(&ballots, i, j)).collect())
+        .collect();
+
+    let points: Vec<(Vec<f64>, f64)> = ballots.iter()
+        .map(|(scores, w)| (scores.iter().map(|s| f64::from(s.unwrap_or(0))).collect(), *w))
+        .collect();
+    let clusters = cluster(&points, MAX_CLUSTERS.min(points.len()))
+        .into_iter()
+        .map(|(centroid, weight)| BallotCluster {
+            share: weight / total_weight,
+            centroid: options.iter().cloned().zip(centroid).collect(),
+        })
+        .collect();
+
+    BallotAnalytics { options, correlation, bullet_share, exaggerated_share, clusters }
+}
+
+fn correlate(ballots: &[(Vec<Option<i8>>, f64)], i: usize, j: usize) -> Option<f64> {
+    let pairs: Vec<(f64, f64, f64)> = ballots.iter()
+        .filter_map(|(scores, w)| Some((f64::from(scores[i]?), f64::from(scores[j]?), *w)))
+        .collect();
+    let weight: f64 = pairs.iter().map(|(_, _, w)| w).sum();
+    if weight == 0.0 {
+        return None;
+    }
+
+    let mean_x = pairs.iter().map(|(x, _, w)| x * w).sum::<f64>() / weight;
+    let mean_y = pairs.iter().map(|(_, y, w)| y * w).sum::<f64>() / weight;
+    let (mut cov, mut var_x, mut var_y) = (0.0, 0.0, 0.0);
+    for (x, y, w) in &pairs {
+        cov += w * (x - mean_x) * (y - mean_y);
+        var_x += w * (x - mean_x).powi(2);
+        var_y += w * (y - mean_y).powi(2);
+    }
+    (var_x > 0.0 && var_y > 0.0).then(|| cov / (var_x * var_y).sqrt())
+}
+
+fn distance(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(x, y)| (x - y).powi(2)).sum()
+}
+
+// Weighted k-means seeded deterministically: the heaviest ballot first, then
+// repeatedly the ballot farthest from every seed so far. Clusters come back
+// heaviest first, with their total weight.
+fn cluster(points: &[(Vec<f64>, f64)], k: usize) -> Vec<(Vec<f64>, f64)> {
+    let Some((heaviest, _)) = points.iter().max_by(|a, b| a.1.total_cmp(&b.1)) else {
+        return Vec::new();
+    };
+    let mut centroids = vec![heaviest.clone()];
+    while centroids.len() < k {
+        let farthest = points.iter()
+            .map(|(p, _)| (p, centroids.iter().map(|c| distance(p, c)).fold(f64::INFINITY, f64::min)))
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+        match farthest {
+            Some((p, d)) if d > 0.0 => centroids.push(p.clone()),
+            _ => break,
+        }
+    }
+
+    let nearest = |p: &[f64], centroids: &[Vec<f64>]| {
+        (0..centroids.len())
+            .min_by(|&a, &b| distance(p, &centroids[a]).total_cmp(&distance(p, &centroids[b])))
+            .unwrap_or(0)
+    };
+
+    let mut weights = vec![0.0; centroids.len()];
+    for _ in 0..CLUSTER_ITERATIONS {
+        let dims = centroids[0].len();
+        let mut sums = vec![vec![0.0; dims]; centroids.len()];
+        weights = vec![0.0; centroids.len()];
+        for (p, w) in points {
+            let c = nearest(p, &centroids);
+            weights[c] += w;
+            sums[c].iter_mut().zip(p).for_each(|(s, x)| *s += x * w);
+        }
+
+        let updated: Vec<Vec<f64>> = sums.into_iter().zip(&weights).zip(&centroids)
+            .map(|((sum, &w), old)| if w > 0.0 { sum.iter().map(|s| s / w).collect() } else { old.clone() })
+            .collect();
+        if updated == centroids {
+            break;
+        }
+        centroids = updated;
+    }
+
+    let mut clusters: Vec<_> = centroids.into_iter().zip(weights).filter(|(_, w)| *w > 0.0).collect();
+    clusters.sort_by(|a, b| b.1.total_cmp(&a.1));
+    clusters
+}
@@ -0,0 +1,91 @@
+//! Monte-carlo comparison of STAR against other single-winner methods.
+//!
+//! ```text
+//! cargo run --bin star-sim -- --model spatial:2 --voters 99 --candidates 3,5,8 --trials 1000
+//! ```
+//!
+//! Prints one CSV row per candidate count and method.
+
+use std::process::ExitCode;
+use shared::simulation::{simulate, SimulationConfig, VoterModel};
+use shared::star_logic::DEFAULT_MAX_SCORE;
+
+const USAGE: &str = "usage: star-sim [--model impartial|spatial[:DIMS]] [--voters N] [--candidates N[,N...]] \
+                     [--trials N] [--max-score N] [--seed N]";
+
+struct Args {
+    model: VoterModel,
+    voters: usize,
+    candidates: Vec<usize>,
+    trials: usize,
+    max_score: i8,
+    seed: u64,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut args = Args {
+        model: VoterModel::Spatial { dimensions: 2 },
+        voters: 99,
+        candidates: vec![3, 5, 8],
+        trials: 1000,
+        max_score: DEFAULT_MAX_SCORE,
+        seed: 1,
+    };
+
+    let mut argv = std::env::args().skip(1);
+    while let Some(flag) = argv.next() {
+        let value = argv.next().ok_or_else(|| format!("missing value for {flag}"))?;
+        let invalid = |_| format!("invalid value for {flag}: {value}");
+        match flag.as_str() {
+            "--model" => args.model = value.parse()?,
+            "--voters" => args.voters = value.parse().map_err(invalid)?,
+            "--candidates" => {
+                args.candidates = value.split(',')
+                    .map(|n| n.trim().parse().map_err(|_| format!("invalid value for {flag}: {value}")))
+                    .collect::<Result<_, _>>()?
+            }
+            "--trials" => args.trials = value.parse().map_err(invalid)?,
+            "--max-score" => args.max_score = value.parse().map_err(invalid)?,
+            "--seed" => args.seed = value.parse().map_err(invalid)?,
+            _ => return Err(format!("unknown flag {flag}")),
+        }
+    }
+    Ok(args)
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args() {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{e}\n{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    println!("model,voters,candidates,trials,method,bayesian_regret,condorcet_efficiency,tie_rate");
+    for &candidates in &args.candidates {
+        let config = SimulationConfig {
+            model: args.model,
+            voters: args.voters,
+            candidates,
+            trials: args.trials,
+            max_score: args.max_score,
+            seed: args.seed,
+        };
+        let metrics = match simulate(&config) {
+            Ok(metrics) => metrics,
+            Err(e) => {
+                eprintln!("{candidates} candidates: {e}");
+                return ExitCode::FAILURE;
+            }
+        };
+        for row in metrics {
+            println!(
+                "{},{},{},{},{},{:.6},{:.4},{:.4}",
+                config.model, config.voters, candidates, config.trials,
+                row.method, row.bayesian_regret, row.condorcet_efficiency, row.tie_rate
+            );
+        }
+    }
+    ExitCode::SUCCESS
+}
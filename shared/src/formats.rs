@@ -0,0 +1,322 @@
+//! Ballot files for exchanging ballots with other tabulators.
+//!
+//! CSV: a header row of option names, then one row per ballot with one score
+//! per option; an empty cell is blank. Weighted ballots are written as that
+//! many identical rows, so BLT suits them better.
+//!
+//! BLT, STAR variant: the usual BLT layout, except that each ballot line holds
+//! one score per option, in option order, rather than a preference order.
+//!
+//! ```text
+//! 3 1 5          options, seats, max score
+//! 2 5 3 - 0      weight, scores ("-" is blank), 0 ends the ballot
+//! 1 0 4 5 0
+//! 0              end of ballots
+//! "Alice"
+//! "Bob"
+//! "Carol"
+//! "Lunch poll"   title
+//! ```
+//!
+//! Names are written on one line each, so a line break in one is escaped as
+//! `\n` or `\r`, and a backslash as `\\`.
+use std::collections::HashSet;
+use crate::star_logic::{Ballot, DEFAULT_MAX_SCORE, MAX_SCORE_LIMIT};
+
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum FormatError {
+    #[error("Missing header")]
+    MissingHeader,
+    #[error("Line {line}: {reason}")]
+    Invalid { line: usize, reason: String },
+}
+
+fn invalid(line: usize, reason: impl Into<String>) -> FormatError {
+    FormatError::Invalid { line, reason: reason.into() }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BallotFile {
+    pub title: String,
+    pub options: Vec<String>,
+    pub seats: usize,
+    pub max_score: i8,
+    pub ballots: Vec<Ballot<String>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BallotFormat {
+    Csv,
+    Blt,
+}
+
+impl BallotFile {
+    pub fn to_csv(&self) -> String {
+        self.write(BallotFormat::Csv)
+    }
+
+    fn write(&self, format: BallotFormat) -> String {
+        let mut file = self.header(format);
+        self.ballots.iter().for_each(|ballot| file.push_str(&self.row(format, ballot)));
+        file.push_str(&self.footer(format));
+        file
+    }
+
+    /// Everything before the ballots. With `row` and `footer` this writes a
+    /// file one ballot at a time, leaving `ballots` unused, so an export need
+    /// not hold every ballot at once.
+    pub fn header(&self, format: BallotFormat) -> String {
+        match format {
+            BallotFormat::Csv => csv_row(&self.options),
+            BallotFormat::Blt => format!("{} {} {}\n", self.options.len(), self.seats, self.max_score),
+        }
+    }
+
+    pub fn row(&self, format: BallotFormat, ballot: &Ballot<String>) -> String {
+        match format {
+            BallotFormat::Csv => {
+                let row = csv_row(self.options.iter().map(|opt| {
+                    ballot.score(opt).map_or(String::new(), |score| score.as_i8().to_string())
+                }));
+                row.repeat(ballot.weight() as usize)
+            }
+            BallotFormat::Blt => {
+                let mut row = ballot.weight().to_string();
+                for option in &self.options {
+                    match ballot.score(option) {
+                        Some(score) => row.push_str(&format!(" {}", score.as_i8())),
+                        None => row.push_str(" -"),
+                    }
+                }
+                row.push_str(" 0\n");
+                row
+            }
+        }
+    }
+
+    pub fn footer(&self, format: BallotFormat) -> String {
+        match format {
+            BallotFormat::Csv => String::new(),
+            BallotFormat::Blt => {
+                let mut footer = String::from("0\n");
+                for name in self.options.iter().chain([&self.title]) {
+                    footer.push_str(&format!("\"{}\"\n", escape_name(name)));
+                }
+                footer
+            }
+        }
+    }
+
+    /// CSV carries no title, seat count or score range; seats default to 1.
+    pub fn from_csv(input: &str, max_score: i8) -> Result<Self, FormatError> {
+        let mut rows = parse_csv(input)?.into_iter()
+            .filter(|(_, fields)| !matches!(&fields[..], [field] if field.trim().is_empty()));
+        let (header_line, header) = rows.next().ok_or(FormatError::MissingHeader)?;
+        let options = header;
+        check_options(header_line, &options)?;
+
+        let ballots = rows
+            .map(|(line, fields)| {
+                if fields.len() != options.len() {
+                    return Err(invalid(line, format!("Expected {} scores, found {}", options.len(), fields.len())));
+                }
+                let scores = fields.iter().map(|field| match field.as_str() {
+                    "" => Ok(None),
+                    score => parse_score(line, score).map(Some),
+                });
+                ballot(line, &options, scores, max_score)
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(BallotFile { title: String::new(), options, seats: 1, max_score, ballots })
+    }
+
+    pub fn to_blt(&self) -> String {
+        self.write(BallotFormat::Blt)
+    }
+
+    pub fn from_blt(input: &str) -> Result<Self, FormatError> {
+        let mut lines = input.lines()
+            .enumerate()
+            .map(|(i, line)| (i + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty());
+
+        let (header_line, header) = lines.next().ok_or(FormatError::MissingHeader)?;
+        let header: Vec<&str> = header.split_whitespace().collect();
+        let number = |field: &str| field.parse::<usize>().map_err(|_| invalid(header_line, format!("Invalid number '{field}'")));
+        let (option_count, seats, max_score) = match header[..] {
+            [options, seats] => (number(options)?, number(seats)?, DEFAULT_MAX_SCORE),
+            [options, seats, max_score] => (
+                number(options)?,
+                number(seats)?,
+                max_score.parse().map_err(|_| invalid(header_line, format!("Invalid max score '{max_score}'")))?,
+            ),
+            _ => return Err(invalid(header_line, "Expected option count, seat count and max score")),
+        };
+        if !(1..=MAX_SCORE_LIMIT).contains(&max_score) {
+            return Err(invalid(header_line, format!("Max score must be between 1 and {MAX_SCORE_LIMIT}")));
+        }
+        if !(1..=option_count).contains(&seats) {
+            return Err(invalid(header_line, format!("Seats must be between 1 and {option_count}")));
+        }
+
+        let mut ballot_lines = Vec::new();
+        loop {
+            let (line, text) = lines.next().ok_or_else(|| invalid(header_line, "Missing end of ballots"))?;
+            if text == "0" {
+                break;
+            }
+            ballot_lines.push((line, text));
+        }
+
+        let mut quoted = || -> Result<(usize, String), FormatError> {
+            let (line, text) = lines.next().ok_or_else(|| invalid(header_line, "Missing option name"))?;
+            let name = text.strip_prefix('"')
+                .and_then(|text| text.strip_suffix('"'))
+                .ok_or_else(|| invalid(line, "Expected a quoted name"))?;
+            unescape_name(name).map(|name| (line, name)).ok_or_else(|| invalid(line, "Invalid escape in name"))
+        };
+        let options = (0..option_count)
+            .map(|_| quoted().map(|(_, name)| name))
+            .collect::<Result<Vec<_>, _>>()?;
+        check_options(header_line, &options)?;
+        let title = quoted().map(|(_, title)| title).unwrap_or_default();
+
+        let ballots = ballot_lines.into_iter()
+            .map(|(line, text)| {
+                let fields: Vec<&str> = text.split_whitespace().collect();
+                let [weight, scores @ .., "0"] = &fields[..] else {
+                    return Err(invalid(line, "Ballot must end with 0"));
+                };
+                if scores.len() != option_count {
+                    return Err(invalid(line, format!("Expected {option_count} scores, found {}", scores.len())));
+                }
+                let weight = weight.parse::<u32>()
+                    .ok()
+                    .filter(|&weight| weight > 0)
+                    .ok_or_else(|| invalid(line, format!("Invalid weight '{weight}'")))?;
+                let scores = scores.iter().map(|&score| match score {
+                    "-" => Ok(None),
+                    score => parse_score(line, score).map(Some),
+                });
+                ballot(line, &options, scores, max_score).map(|ballot| ballot.with_weight(weight))
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(BallotFile { title, options, seats, max_score, ballots })
+    }
+}
+
+fn check_options(line: usize, options: &[String]) -> Result<(), FormatError> {
+    let mut seen = HashSet::new();
+    match options.iter().find(|opt| opt.is_empty() || !seen.insert(*opt)) {
+        Some(opt) if opt.is_empty() => Err(invalid(line, "Empty option name")),
+        Some(opt) => Err(invalid(line, format!("Duplicate option '{opt}'"))),
+        None => Ok(()),
+    }
+}
+
+fn escape_name(name: &str) -> String {
+    name.replace('\\', "\\\\").replace('\n', "\\n").replace('\r', "\\r")
+}
+
+fn unescape_name(name: &str) -> Option<String> {
+    let mut unescaped = String::with_capacity(name.len());
+    let mut chars = name.chars();
+    while let Some(c) = chars.next() {
+        unescaped.push(match c {
+            '\\' => match chars.next()? {
+                '\\' => '\\',
+                'n' => '\n',
+                'r' => '\r',
+                _ => return None,
+            },
+            c => c,
+        });
+    }
+    Some(unescaped)
+}
+
+fn parse_score(line: usize, score: &str) -> Result<i8, FormatError> {
+    score.parse().map_err(|_| invalid(line, format!("Invalid score '{score}'")))
+}
+
+fn ballot(
+    line: usize,
+    options: &[String],
+    scores: impl Iterator<Item = Result<Option<i8>, FormatError>>,
+    max_score: i8,
+) -> Result<Ballot<String>, FormatError> {
+    let mut filled = Vec::new();
+    for (option, score) in options.iter().zip(scores) {
+        if let Some(score) = score? {
+            filled.push((option.clone(), score));
+        }
+    }
+    Ballot::with_max_score(filled, max_score).map_err(|e| invalid(line, e.to_string()))
+}
+
+fn csv_row<S: AsRef<str>>(fields: impl IntoIterator<Item = S>) -> String {
+    let fields: Vec<_> = fields.into_iter()
+        .map(|field| {
+            let field = field.as_ref();
+            if field.contains([',', '"', '\n', '\r']) || field.trim() != field {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field.to_string()
+            }
+        })
+        .collect();
+    format!("{}\n", fields.join(","))
+}
+
+/// RFC 4180 rows, each with the line it starts on. Unquoted fields are
+/// trimmed; quoted ones are kept exactly, padding included.
+fn parse_csv(input: &str) -> Result<Vec<(usize, Vec<String>)>, FormatError> {
+    let mut rows = Vec::new();
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let (mut line, mut row_line) = (1, 1);
+    let mut chars = input.chars().peekable();
+    let (mut quoted, mut was_quoted) = (false, false);
+    let finish = |field: &mut String, was_quoted: &mut bool| {
+        let field = std::mem::take(field);
+        if std::mem::take(was_quoted) { field } else { field.trim().to_string() }
+    };
+
+    while let Some(c) = chars.next() {
+        match (quoted, c) {
+            (true, '"') if chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            (true, '"') => quoted = false,
+            (true, c) => {
+                line += usize::from(c == '\n');
+                field.push(c);
+            }
+            (false, '"') if field.trim().is_empty() => {
+                field.clear();
+                quoted = true;
+                was_quoted = true;
+            }
+            (false, ',') => fields.push(finish(&mut field, &mut was_quoted)),
+            (false, '\r') if chars.peek() == Some(&'\n') => {}
+            (false, '\n') => {
+                fields.push(finish(&mut field, &mut was_quoted));
+                rows.push((row_line, std::mem::take(&mut fields)));
+                line += 1;
+                row_line = line;
+            }
+            (false, c) => field.push(c),
+        }
+    }
+    if quoted {
+        return Err(invalid(row_line, "Unterminated quoted field"));
+    }
+    if !field.is_empty() || !fields.is_empty() {
+        fields.push(finish(&mut field, &mut was_quoted));
+        rows.push((row_line, fields));
+    }
+    Ok(rows)
+}
@@ -3,12 +3,23 @@ pub mod models;
 pub mod validation;
 pub mod user_info;
 pub mod star_logic;
+pub mod analytics;
+pub mod simulation;
+pub mod formats;
 
 pub use error::{Error, ErrorCode, Result, ErrorResponse};
 pub use models::*;
 pub use validation::*;
 pub use user_info::*;
-pub use star_logic::{Ballot, Election, Score, VotingError, HeadToHeadMatchup, RunoffResult};
+pub use analytics::{analyze, BallotAnalytics, BallotCluster};
+pub use formats::{BallotFile, BallotFormat, FormatError};
+pub use star_logic::{
+    Ballot, Election, Score, VotingError, HeadToHeadMatchup, RunoffResult, PairwiseAnalysis,
+    TiebreakRule, TiebreakStage, TiebreakStep, TiebreakTrace, MultiWinnerMethod, MultiWinnerResult,
+    WinnerRound, TabulationMethod, Star, ScoreVoting, Approval, Plurality, RankedRobin, comparison_methods,
+    PlaceReason, RankedPlace, BlankPolicy, ResultMismatch,
+    VictoryMargin, ChallengerMargin, TabulationOutcome,
+};
 
 #[cfg(test)]
 mod tests;
\ No newline at end of file
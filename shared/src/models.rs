@@ -2,14 +2,29 @@ use serde::{Serialize, Deserialize};
 use std::collections::HashMap;
 use time::OffsetDateTime;
 use uuid::Uuid;
+use crate::star_logic::{
+    BlankPolicy, HeadToHeadMatchup, MultiWinnerMethod, RankedPlace, ResultMismatch, TabulationOutcome, TiebreakTrace,
+    VictoryMargin, WinnerRound, DEFAULT_MAX_SCORE,
+};
 
 #[cfg(feature = "backend")]
-#[derive(sqlx::Type)]
-#[sqlx(type_name = "vote_state", rename_all = "lowercase")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, sqlx::Type)]
+#[sqlx(type_name = "vote_state", rename_all = "snake_case")]
 pub enum VoteState {
+    Scheduled,
     Active,
     Concluded,
-    PendingDeletion,
+}
+
+#[cfg(feature = "backend")]
+impl VoteState {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            VoteState::Scheduled => "scheduled",
+            VoteState::Active => "active",
+            VoteState::Concluded => "concluded",
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -20,10 +35,107 @@ pub struct Vote {
     pub description: String,
     pub options: Vec<String>,
     pub voting_ends_at: OffsetDateTime,
-    pub ballots: Vec<VoteBallot>,
+    /// How many ballots have been cast; the ballots themselves are paged
+    /// through `BallotPage` once voting ends.
+    #[serde(default)]
+    pub ballot_count: i64,
     pub duration_hours: i32,
     pub duration_minutes: i32,
     pub user_fingerprint: String,
+    pub seats: i32,
+    pub winner_method: MultiWinnerMethod,
+    pub max_score: i32,
+    pub weighted: bool,
+    pub blank_policy: BlankPolicy,
+    #[serde(default)]
+    pub withdrawn_options: Vec<String>,
+    #[serde(default)]
+    pub results_visibility: ResultsVisibility,
+    /// When ballots start being accepted; `None` if voting opened on creation.
+    #[serde(default)]
+    pub opens_at: Option<OffsetDateTime>,
+    #[serde(default)]
+    pub visibility: VoteVisibility,
+    /// Seeds the random-lot tiebreak. Drawn secretly when the vote is created
+    /// and only filled in once voting ends, so ties cannot be predicted.
+    #[serde(default)]
+    pub tiebreak_seed: Option<u64>,
+}
+
+/// Where a vote can be found. Unlisted votes are left off the public list but
+/// open to anyone with the link; private votes also need an access code.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VoteVisibility {
+    #[default]
+    Public,
+    Unlisted,
+    Private,
+}
+
+impl VoteVisibility {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            VoteVisibility::Public => "public",
+            VoteVisibility::Unlisted => "unlisted",
+            VoteVisibility::Private => "private",
+        }
+    }
+}
+
+impl std::str::FromStr for VoteVisibility {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            "public" => Ok(VoteVisibility::Public),
+            "unlisted" => Ok(VoteVisibility::Unlisted),
+            "private" => Ok(VoteVisibility::Private),
+            other => Err(format!("Unknown vote visibility: {other}")),
+        }
+    }
+}
+
+/// Who may see the running tally before voting ends.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResultsVisibility {
+    #[default]
+    HiddenUntilClose,
+    Live,
+    CreatorOnly,
+}
+
+impl ResultsVisibility {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            ResultsVisibility::HiddenUntilClose => "hidden_until_close",
+            ResultsVisibility::Live => "live",
+            ResultsVisibility::CreatorOnly => "creator_only",
+        }
+    }
+
+    /// Whether the running tally may be shown before voting ends.
+    pub const fn shows_provisional(self, is_creator: bool) -> bool {
+        match self {
+            ResultsVisibility::HiddenUntilClose => false,
+            ResultsVisibility::Live => true,
+            ResultsVisibility::CreatorOnly => is_creator,
+        }
+    }
+}
+
+impl std::str::FromStr for ResultsVisibility {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            "hidden_until_close" => Ok(ResultsVisibility::HiddenUntilClose),
+            "live" => Ok(ResultsVisibility::Live),
+            "creator_only" => Ok(ResultsVisibility::CreatorOnly),
+            other => Err(format!("Unknown results visibility: {other}")),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -31,8 +143,61 @@ pub struct Vote {
 pub struct VoteBallot {
     pub csrf_token: String,
     pub captcha_token: String,
-    pub scores: HashMap<String, i8>,
+    /// `None` (or a missing option) is an explicit "no opinion".
+    pub scores: HashMap<String, Option<i8>>,
     pub user_fingerprint: String,
+    #[serde(default)]
+    pub credential: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WithdrawOptionRequest {
+    pub csrf_token: String,
+    pub management_token: String,
+    pub option: String,
+}
+
+/// Proof of ownership for the management endpoints that need nothing else:
+/// ending and deleting a vote.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManageVoteRequest {
+    pub csrf_token: String,
+    pub management_token: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EditDescriptionRequest {
+    pub csrf_token: String,
+    pub management_token: String,
+    pub description: String,
+}
+
+/// Pushes the deadline back by `hours` and `minutes`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtendVoteRequest {
+    pub csrf_token: String,
+    pub management_token: String,
+    pub hours: i32,
+    pub minutes: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IssueCredentialsRequest {
+    pub csrf_token: String,
+    pub management_token: String,
+    pub weights: Vec<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct VoterCredential {
+    pub token: String,
+    pub weight: u32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -43,6 +208,23 @@ pub struct BallotResponse {
     pub cast_at: OffsetDateTime,
 }
 
+/// A slice of a concluded vote's ballots in the order they were cast.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BallotPage {
+    pub ballots: Vec<BallotRow>,
+    /// Pass as `after` to fetch the next page; `None` on the last one.
+    pub next: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BallotRow {
+    /// One entry per vote option, in the vote's order; `None` is no opinion.
+    pub scores: Vec<Option<i8>>,
+    pub weight: u32,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct VoteStats {
@@ -50,13 +232,37 @@ pub struct VoteStats {
     pub option_scores: HashMap<String, VoteOptionStats>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+/// `VoteStats` plus the ranking, as one flat object. The fields are spelled out
+/// rather than `#[serde(flatten)]`ed, which loses the integer keys of `frequency`.
+pub struct FinalStats {
+    pub total_ballots: usize,
+    pub option_scores: HashMap<String, VoteOptionStats>,
+    /// `None` on votes archived before the ranking was stored.
+    #[serde(default)]
+    pub ranking: Option<Vec<RankedPlace<String>>>,
+}
+
+impl FinalStats {
+    pub fn new(stats: VoteStats, ranking: Vec<RankedPlace<String>>) -> Self {
+        Self { total_ballots: stats.total_ballots, option_scores: stats.option_scores, ranking: Some(ranking) }
+    }
+
+    pub fn into_parts(self) -> (VoteStats, Option<Vec<RankedPlace<String>>>) {
+        (VoteStats { total_ballots: self.total_ballots, option_scores: self.option_scores }, self.ranking)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct VoteOptionStats {
-    pub total_score: i32,
+    pub total_score: i64,
     pub average_score: f64,
     pub frequency: HashMap<i8, usize>,
     pub total_votes: usize,
+    #[serde(default)]
+    pub abstentions: usize,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -70,9 +276,43 @@ pub struct CreateVoteRequest {
     pub duration_hours: i32,
     pub duration_minutes: i32,
     pub user_fingerprint: String,
+    #[serde(default = "default_seats")]
+    pub seats: i32,
+    #[serde(default)]
+    pub winner_method: MultiWinnerMethod,
+    #[serde(default = "default_max_score")]
+    pub max_score: i32,
+    #[serde(default)]
+    pub weighted: bool,
+    #[serde(default)]
+    pub blank_policy: BlankPolicy,
+    #[serde(default)]
+    pub results_visibility: ResultsVisibility,
+    /// Open voting later rather than straight away; the duration counts from here.
+    #[serde(default)]
+    pub opens_at: Option<OffsetDateTime>,
+    #[serde(default)]
+    pub visibility: VoteVisibility,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// The new vote, plus the token that lets its creator manage it and, for
+/// private votes, the access code voters need. Neither is stored, so they
+/// cannot be retrieved again.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateVoteResponse {
+    #[serde(flatten)]
+    pub vote: Vote,
+    pub management_token: String,
+    #[serde(default)]
+    pub access_code: Option<String>,
+}
+
+fn default_seats() -> i32 { 1 }
+
+fn default_max_score() -> i32 { i32::from(DEFAULT_MAX_SCORE) }
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct HeadToHeadResult {
     pub finalist1: String,
@@ -85,16 +325,70 @@ pub struct HeadToHeadResult {
 #[serde(rename_all = "camelCase")]
 pub struct VoteResult {
     pub winner: Option<String>,
-    pub error: Option<String>,
+    pub winners: Vec<String>,
+    pub seat_rounds: Vec<WinnerRound<String>>,
+    pub ranking: Vec<RankedPlace<String>>,
+    pub outcome: TabulationOutcome<String>,
     pub stats: VoteStats,
     pub head_to_head: Option<HeadToHeadResult>,
+    pub runner_up_matchups: Vec<HeadToHeadMatchup<String>>,
+    pub tiebreak: Option<TiebreakTrace<String>>,
+    #[serde(default)]
+    pub margin: Option<VictoryMargin<String>>,
+    /// Options withdrawn after voting ended; this result ignores their scores.
+    #[serde(default)]
+    pub withdrawn: Vec<String>,
+    /// The result before any withdrawal.
+    #[serde(default)]
+    pub original: Option<Box<VoteResult>>,
+    /// A running tally of a vote that is still open; not the final result.
+    #[serde(default)]
+    pub provisional: bool,
     pub duration_hours: Option<i64>,
     pub duration_minutes: Option<i64>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct MethodComparison {
+    pub method: String,
+    pub winner: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RecountReport {
+    pub vote_id: Uuid,
+    pub matches: bool,
+    pub result: Vec<ResultMismatch<String>>,
+    pub fields: Vec<FieldMismatch>,
+}
+
+/// A stored value that differs from the recount, both rendered as JSON.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldMismatch {
+    pub field: String,
+    pub stored: String,
+    pub recounted: String,
+}
+
 impl Vote {
     pub fn is_ended(&self) -> bool {
-        OffsetDateTime::now_utc() > self.voting_ends_at
+        self.is_ended_at(OffsetDateTime::now_utc())
+    }
+
+    pub fn is_ended_at(&self, now: OffsetDateTime) -> bool {
+        now > self.voting_ends_at
+    }
+
+    pub fn has_opened(&self) -> bool {
+        self.has_opened_at(OffsetDateTime::now_utc())
+    }
+
+    pub fn has_opened_at(&self, now: OffsetDateTime) -> bool {
+        self.opens_at.is_none_or(|opens_at| now >= opens_at)
     }
 
     pub fn end_now(&mut self) {
@@ -102,6 +396,6 @@ impl Vote {
     }
 
     pub fn total_votes(&self) -> usize {
-        self.ballots.len()
+        self.ballot_count as usize
     }
 }
\ No newline at end of file
@@ -0,0 +1,187 @@
+use serde::{Deserialize, Serialize};
+use crate::star_logic::{comparison_methods, Ballot, Election, SplitMix64, VotingError};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VoterModel {
+    /// Every voter's utility for every candidate is independent and uniform.
+    ImpartialCulture,
+    /// Voters and candidates are points in a unit cube; utility falls with distance.
+    Spatial { dimensions: usize },
+}
+
+impl std::fmt::Display for VoterModel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VoterModel::ImpartialCulture => write!(f, "impartial"),
+            VoterModel::Spatial { dimensions } => write!(f, "spatial:{dimensions}"),
+        }
+    }
+}
+
+impl std::str::FromStr for VoterModel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s.split_once(':') {
+            None if s == "impartial" => Ok(VoterModel::ImpartialCulture),
+            None if s == "spatial" => Ok(VoterModel::Spatial { dimensions: 2 }),
+            Some(("spatial", dims)) => dims.parse()
+                .ok()
+                .filter(|&dimensions| dimensions > 0)
+                .map(|dimensions| VoterModel::Spatial { dimensions })
+                .ok_or_else(|| format!("Invalid spatial dimensions: {dims}")),
+            _ => Err(format!("Unknown voter model: {s}")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulationConfig {
+    pub model: VoterModel,
+    pub voters: usize,
+    pub candidates: usize,
+    pub trials: usize,
+    pub max_score: i8,
+    pub seed: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MethodMetrics {
+    pub method: String,
+    /// Average utility per voter lost against always electing the best candidate.
+    pub bayesian_regret: f64,
+    /// Share of trials with a sincere Condorcet winner in which it was elected.
+    pub condorcet_efficiency: f64,
+    /// Share of trials the method could only settle by lot.
+    pub tie_rate: f64,
+}
+
+#[derive(Default)]
+struct Tally {
+    regret: f64,
+    condorcet_wins: usize,
+    ties: usize,
+}
+
+/// Runs `config.trials` synthetic elections and scores every method from
+/// `comparison_methods` against the voters' underlying utilities. Voters
+/// score honestly, scaling their favourite to the top score and their least
+/// favourite to 0. The same seed always yields the same metrics.
+pub fn simulate(config: &SimulationConfig) -> Result<Vec<MethodMetrics>, VotingError<usize>> {
+    let methods = comparison_methods::<usize>(config.max_score);
+    let mut tallies: Vec<Tally> = methods.iter().map(|_| Tally::default()).collect();
+    let mut condorcet_trials = 0;
+    let mut rng = SplitMix64::new(config.seed);
+
+    for trial in 0..config.trials {
+        let utilities = config.model.utilities(config.voters, config.candidates, &mut rng);
+        let ballots = honest_ballots(&utilities, config.max_score)?;
+        let totals: Vec<f64> = (0..config.candidates)
+            .map(|c| utilities.iter().map(|voter| voter[c]).sum())
+            .collect();
+        let best = totals.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let condorcet_winner = sincere_condorcet_winner(&utilities);
+        condorcet_trials += usize::from(condorcet_winner.is_some());
+
+        let election = build_election(config, &ballots, None)?;
+        // Built only when some method needs the random lot to finish.
+        let mut lot_election = None;
+        for (method, tally) in methods.iter().zip(&mut tallies) {
+            let winner = match method.winner(&election) {
+                Ok(winner) => winner,
+                Err(VotingError::FirstPlaceTie(_) | VotingError::SecondPlaceTie(_)) => {
+                    tally.ties += 1;
+                    let by_lot = match lot_election.take() {
+                        Some(by_lot) => by_lot,
+                        None => build_election(config, &ballots, Some(config.seed ^ trial as u64))?,
+                    };
+                    let winner = method.winner(&by_lot)?;
+                    lot_election = Some(by_lot);
+                    winner
+                }
+                Err(e) => return Err(e),
+            };
+            tally.regret += (best - totals[winner]) / config.voters.max(1) as f64;
+            tally.condorcet_wins += usize::from(condorcet_winner == Some(winner));
+        }
+    }
+
+    let trials = config.trials.max(1) as f64;
+    Ok(methods.iter().zip(tallies)
+        .map(|(method, tally)| MethodMetrics {
+            method: method.name().into(),
+            bayesian_regret: tally.regret / trials,
+            condorcet_efficiency: if condorcet_trials > 0 {
+                tally.condorcet_wins as f64 / condorcet_trials as f64
+            } else {
+                0.0
+            },
+            tie_rate: tally.ties as f64 / trials,
+        })
+        .collect())
+}
+
+impl VoterModel {
+    fn utilities(self, voters: usize, candidates: usize, rng: &mut SplitMix64) -> Vec<Vec<f64>> {
+        match self {
+            VoterModel::ImpartialCulture => (0..voters)
+                .map(|_| (0..candidates).map(|_| rng.next_f64()).collect())
+                .collect(),
+            VoterModel::Spatial { dimensions } => {
+                let mut point = || (0..dimensions).map(|_| rng.next_f64()).collect::<Vec<_>>();
+                let positions: Vec<_> = (0..candidates).map(|_| point()).collect();
+                (0..voters)
+                    .map(|_| {
+                        let voter = point();
+                        positions.iter()
+                            .map(|candidate| -voter.iter().zip(candidate).map(|(a, b)| (a - b).powi(2)).sum::<f64>().sqrt())
+                            .collect()
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+fn honest_ballots(utilities: &[Vec<f64>], max_score: i8) -> Result<Vec<Ballot<usize>>, VotingError<usize>> {
+    utilities.iter()
+        .map(|voter| {
+            let low = voter.iter().copied().fold(f64::INFINITY, f64::min);
+            let high = voter.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+            let scores = voter.iter().enumerate()
+                .map(|(c, u)| {
+                    let scaled = if high > low { (u - low) / (high - low) } else { 0.0 };
+                    (c, (scaled * f64::from(max_score)).round() as i8)
+                });
+            Ballot::with_max_score(scores, max_score)
+        })
+        .collect()
+}
+
+fn build_election(
+    config: &SimulationConfig,
+    ballots: &[Ballot<usize>],
+    seed: Option<u64>,
+) -> Result<Election<usize>, VotingError<usize>> {
+    let election = match seed {
+        Some(seed) => Election::with_tiebreak_seed(seed),
+        None => Election::new(),
+    };
+    let mut election = election.with_max_score(config.max_score)?;
+    for candidate in 0..config.candidates {
+        election.add_option(candidate)?;
+    }
+    for ballot in ballots {
+        election.cast_ballot(ballot.clone())?;
+    }
+    Ok(election)
+}
+
+fn sincere_condorcet_winner(utilities: &[Vec<f64>]) -> Option<usize> {
+    let candidates = utilities.first().map_or(0, Vec::len);
+    let prefers = |a: usize, b: usize| utilities.iter().filter(|voter| voter[a] > voter[b]).count();
+    (0..candidates).find(|&a| (0..candidates).all(|b| a == b || prefers(a, b) > prefers(b, a)))
+}
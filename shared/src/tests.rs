@@ -1,10 +1,20 @@
 #[cfg(test)]
 mod tests {
     use std::{fmt::Debug, hash::Hash};
-    use crate::star_logic::{Ballot, Election, Score, VotingError, RunoffResult};
+    use crate::star_logic::{
+        Ballot, Election, Score, VotingError, RunoffResult, TiebreakRule, TiebreakStage,
+        MultiWinnerMethod, TabulationMethod, Plurality, comparison_methods, PlaceReason,
+        BlankPolicy, ResultMismatch, VictoryMargin, ChallengerMargin, TabulationOutcome, SplitMix64,
+    };
+    use crate::analytics::analyze;
+    use crate::simulation::{simulate, SimulationConfig, VoterModel};
+    use crate::formats::{BallotFile, BallotFormat, FormatError};
+    use crate::models::{FinalStats, ResultsVisibility, Vote, VoteVisibility};
+    use time::{Duration, OffsetDateTime};
+    use uuid::Uuid;
 
     fn ballot<T: Clone + Eq + Hash + Debug>(scores: &[(T, i8)]) -> Ballot<T> {
-        Ballot::new(scores.iter().cloned().collect()).unwrap()
+        Ballot::new(scores.iter().cloned()).unwrap()
     }
 
     fn election<T: Clone + Eq + Hash + Ord + Debug>(options: &[T]) -> Election<T> {
@@ -13,14 +23,37 @@ mod tests {
         e
     }
 
+    const OPTIONS: [&str; 6] = ["A", "B", "C", "D", "E", "F"];
+
+    fn random_case(rng: &mut SplitMix64) -> (Vec<&'static str>, Vec<Ballot<&'static str>>) {
+        let options = OPTIONS[..2 + rng.below(5)].to_vec();
+        let max_score = 1 + rng.below(5) as i8;
+        let ballots = (0..1 + rng.below(12))
+            .map(|_| {
+                let scores: Vec<_> = options.iter()
+                    .map(|opt| (*opt, rng.below(max_score as usize + 1) as i8))
+                    .collect();
+                ballot(&scores)
+            })
+            .collect();
+        (options, ballots)
+    }
+
+    fn tabulate(seed: u64, options: &[&'static str], ballots: &[Ballot<&'static str>]) -> String {
+        let mut e = Election::with_tiebreak_seed(seed);
+        options.iter().for_each(|opt| e.add_option(*opt).unwrap());
+        ballots.iter().for_each(|b| e.cast_ballot(b.clone()).unwrap());
+        format!("{:?}", e.determine_winner())
+    }
+
     #[test]
     fn test_basic_validation() {
         assert!(Score::try_from(0).is_ok());
         assert!(Score::try_from(6).is_err());
 
-        assert!(Ballot::<&str>::new([("A", 5)].iter().cloned().collect()).is_ok());
+        assert!(Ballot::<&str>::new([("A", 5)]).is_ok());
         assert!(matches!(
-            Ballot::<&str>::new([("A", 6)].iter().cloned().collect()),
+            Ballot::<&str>::new([("A", 6)]),
             Err(VotingError::InvalidScore(6))
         ));
 
@@ -33,7 +66,7 @@ mod tests {
         let mut e = election(&["A", "B"]);
         e.cast_ballot(ballot(&[("A", 5), ("B", 5)])).unwrap();
         e.cast_ballot(ballot(&[("A", 5), ("B", 5)])).unwrap();
-        assert!(matches!(e.determine_winner(), Err(VotingError::FirstPlaceTie)));
+        assert!(matches!(e.determine_winner(), Err(VotingError::FirstPlaceTie(_))));
 
         let mut e = election(&["A", "B", "C"]);
         let ballots = [
@@ -102,7 +135,7 @@ mod tests {
             ballot(&[("A", 5), ("B", 5), ("C", 5), ("D", 5)]),
         ];
         for b in ballots { e.cast_ballot(b).unwrap(); }
-        assert!(matches!(e.determine_winner(), Err(VotingError::FirstPlaceTie)));
+        assert!(matches!(e.determine_winner(), Err(VotingError::FirstPlaceTie(_))));
     }
 
     #[test]
@@ -137,18 +170,18 @@ mod tests {
     #[test]
     fn test_invalid_ballot_scores() {
         assert!(matches!(
-            Ballot::<&str>::new([("A", 6)].iter().cloned().collect()),
+            Ballot::<&str>::new([("A", 6)]),
             Err(VotingError::InvalidScore(6))
         ));
         assert!(matches!(
-            Ballot::<&str>::new([("A", -1)].iter().cloned().collect()),
+            Ballot::<&str>::new([("A", -1)]),
             Err(VotingError::InvalidScore(-1))
         ));
     }
 
     #[test]
     fn test_empty_ballot() {
-        let empty_ballot = Ballot::<&str>::new([].iter().cloned().collect());
+        let empty_ballot = Ballot::<&str>::new([]);
         assert!(matches!(empty_ballot, Ok(_)), "Empty ballot should be allowed");
     }
 
@@ -165,7 +198,7 @@ mod tests {
         let zero_ballot = ballot(&[("A", 0), ("B", 0), ("C", 0)]);
         assert!(e.cast_ballot(zero_ballot).is_ok(), "All-zero scores should be accepted");
         let result = e.determine_winner();
-        assert!(result.is_ok() || matches!(result, Err(VotingError::FirstPlaceTie)), "All zero scores should be valid but may result in a tie");
+        assert!(result.is_ok() || matches!(result, Err(VotingError::FirstPlaceTie(_))), "All zero scores should be valid but may result in a tie");
     }
 
     #[test]
@@ -190,4 +223,770 @@ mod tests {
         assert!(matches!(e.determine_winner(), Err(VotingError::InsufficientOptions)));
     }
 
-}
\ No newline at end of file
+    #[test]
+    fn test_tiebreak_head_to_head_among_tied() {
+        let mut e = election(&["A", "B", "C"]);
+        [
+            ballot(&[("A", 0), ("B", 1), ("C", 0)]),
+            ballot(&[("A", 0), ("B", 1), ("C", 0)]),
+            ballot(&[("A", 4), ("B", 0), ("C", 4)]),
+            ballot(&[("A", 0), ("B", 2), ("C", 0)]),
+        ]
+        .iter()
+        .for_each(|b| e.cast_ballot(b.clone()).unwrap());
+        let result = e.determine_winner().unwrap();
+        assert_eq!(result.finalist1, "B");
+        let step = &result.tiebreak.steps[0];
+        assert_eq!(step.stage, TiebreakStage::FirstFinalist);
+        assert_eq!(step.rule, TiebreakRule::HeadToHead);
+        assert_eq!(step.advanced, vec!["B"]);
+    }
+
+    #[test]
+    fn test_tiebreak_five_star_count() {
+        let mut e = election(&["X", "A", "B"]);
+        e.cast_ballot(ballot(&[("X", 5), ("A", 5), ("B", 1)])).unwrap();
+        e.cast_ballot(ballot(&[("X", 5), ("A", 0), ("B", 4)])).unwrap();
+        let result = e.determine_winner().unwrap();
+        let rules: Vec<_> = result.tiebreak.steps.iter().map(|s| s.rule).collect();
+        assert_eq!(rules, vec![TiebreakRule::HeadToHead, TiebreakRule::TopScoreCount]);
+        assert_eq!(result.finalist2, "A");
+    }
+
+    #[test]
+    fn test_tiebreak_seeded_lot() {
+        let run = |seed| {
+            let mut e = Election::with_tiebreak_seed(seed);
+            ["A", "B", "C", "D"].iter().for_each(|opt| e.add_option(*opt).unwrap());
+            e.cast_ballot(ballot(&[("A", 5), ("B", 5), ("C", 5), ("D", 5)])).unwrap();
+            e.determine_winner().unwrap()
+        };
+        let result = run(7);
+        assert_eq!(result, run(7));
+        assert_eq!(result.tiebreak.steps.last().unwrap().stage, TiebreakStage::Runoff);
+        assert_eq!(result.tiebreak.steps.last().unwrap().rule, TiebreakRule::RandomLot);
+        let winners: std::collections::HashSet<_> = (0..32).map(|seed| run(seed).winner).collect();
+        assert!(winners.len() > 1, "Different seeds should be able to draw different winners");
+    }
+
+    #[test]
+    fn test_runoff_tie_resolved_by_score() {
+        let mut e = election(&["A", "B"]);
+        e.cast_ballot(ballot(&[("A", 5), ("B", 0)])).unwrap();
+        e.cast_ballot(ballot(&[("A", 3), ("B", 4)])).unwrap();
+        let result = e.determine_winner().unwrap();
+        assert_eq!(result.head_to_head, (1, 1));
+        assert_eq!(result.winner, "A");
+        let step = result.tiebreak.stage(TiebreakStage::Runoff).next().unwrap();
+        assert_eq!(step.rule, TiebreakRule::ScoreTotal);
+    }
+
+    #[test]
+    fn test_failed_ballot_leaves_totals_untouched() {
+        let mut e = election(&["A", "B"]);
+        assert!(e.cast_ballot(ballot(&[("A", 5), ("C", 5)])).is_err());
+        e.cast_ballot(ballot(&[("A", 0), ("B", 1)])).unwrap();
+        assert_eq!(e.determine_winner().unwrap().winner, "B");
+    }
+
+    #[test]
+    fn test_options_keep_insertion_order() {
+        let e = election(&["Z", "A", "M"]);
+        assert_eq!(e.options().copied().collect::<Vec<_>>(), vec!["Z", "A", "M"]);
+    }
+
+    #[test]
+    fn prop_winner_invariant_to_ballot_order() {
+        let mut rng = SplitMix64::new(0x5354_4152);
+        for case in 0..500 {
+            let (options, mut ballots) = random_case(&mut rng);
+            let seed = rng.next_u64();
+            let expected = tabulate(seed, &options, &ballots);
+
+            ballots.reverse();
+            assert_eq!(tabulate(seed, &options, &ballots), expected, "case {case}: reversed");
+
+            for i in (1..ballots.len()).rev() {
+                ballots.swap(i, rng.below(i + 1));
+            }
+            assert_eq!(tabulate(seed, &options, &ballots), expected, "case {case}: shuffled");
+        }
+    }
+
+    #[test]
+    fn prop_tabulation_is_reproducible() {
+        let mut rng = SplitMix64::new(0x0052_554E);
+        for case in 0..500 {
+            let (options, ballots) = random_case(&mut rng);
+            let seed = rng.next_u64();
+            let expected = tabulate(seed, &options, &ballots);
+            for _ in 0..4 {
+                assert_eq!(tabulate(seed, &options, &ballots), expected, "case {case}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_seeded_lot_is_stable_across_runs() {
+        // Fixed expectations: a change here means archived results would re-tabulate differently.
+        let tied = [ballot(&[("A", 5), ("B", 5), ("C", 5), ("D", 5)])];
+        let winners: Vec<_> = (0..6)
+            .map(|seed| {
+                let mut e = Election::with_tiebreak_seed(seed);
+                ["A", "B", "C", "D"].iter().for_each(|opt| e.add_option(*opt).unwrap());
+                tied.iter().for_each(|b| e.cast_ballot(b.clone()).unwrap());
+                e.determine_winner().unwrap().winner
+            })
+            .collect();
+        assert_eq!(winners, vec!["D", "A", "C", "C", "B", "C"]);
+    }
+
+    #[test]
+    fn test_runner_up_matchups() {
+        let mut e = election(&["A", "B", "C", "D"]);
+        [
+            ballot(&[("A", 5), ("B", 4), ("C", 3), ("D", 0)]),
+            ballot(&[("A", 4), ("B", 5), ("C", 1), ("D", 2)]),
+            ballot(&[("A", 3), ("B", 0), ("C", 4), ("D", 1)]),
+        ]
+        .iter()
+        .for_each(|b| e.cast_ballot(b.clone()).unwrap());
+        let result = e.determine_winner().unwrap();
+        assert_eq!(result.winner, "A");
+        let opponents: Vec<_> = result.runner_up_matchups.iter().map(|m| m.candidate2).collect();
+        assert_eq!(opponents, vec!["C", "D"]);
+        assert!(result.runner_up_matchups.iter().all(|m| m.candidate1 == "A"));
+        assert_eq!(
+            (result.runner_up_matchups[0].votes1, result.runner_up_matchups[0].votes2),
+            (2, 1)
+        );
+    }
+
+    #[test]
+    fn test_pairwise_condorcet_winner() {
+        let mut e = election(&["A", "B", "C"]);
+        [
+            ballot(&[("A", 5), ("B", 4), ("C", 0)]),
+            ballot(&[("A", 5), ("B", 4), ("C", 0)]),
+            ballot(&[("A", 0), ("B", 5), ("C", 4)]),
+        ]
+        .iter()
+        .for_each(|b| e.cast_ballot(b.clone()).unwrap());
+        let pairwise = e.pairwise_analysis();
+        assert_eq!(pairwise.options, vec!["A", "B", "C"]);
+        assert_eq!(pairwise.matrix, vec![vec![0, 2, 2], vec![1, 0, 3], vec![1, 0, 0]]);
+        assert_eq!(pairwise.condorcet_winner, Some("A"));
+        assert_eq!(pairwise.condorcet_loser, Some("C"));
+        assert_eq!(pairwise.smith_set, vec!["A"]);
+        assert!(pairwise.beats(1, 2));
+    }
+
+    #[test]
+    fn test_pairwise_cycle_smith_set() {
+        let mut e = election(&["A", "B", "C", "D"]);
+        [
+            ballot(&[("A", 5), ("B", 4), ("C", 3), ("D", 0)]),
+            ballot(&[("A", 3), ("B", 5), ("C", 4), ("D", 0)]),
+            ballot(&[("A", 4), ("B", 3), ("C", 5), ("D", 0)]),
+        ]
+        .iter()
+        .for_each(|b| e.cast_ballot(b.clone()).unwrap());
+        let pairwise = e.pairwise_analysis();
+        assert_eq!(pairwise.condorcet_winner, None);
+        assert_eq!(pairwise.condorcet_loser, Some("D"));
+        assert_eq!(pairwise.smith_set, vec!["A", "B", "C"]);
+    }
+
+    fn factional_election() -> Election<&'static str> {
+        let mut e = election(&["A", "B", "C", "D"]);
+        let majority = ballot(&[("A", 5), ("B", 4), ("C", 0), ("D", 0)]);
+        let minority = ballot(&[("A", 0), ("B", 0), ("C", 5), ("D", 4)]);
+        (0..6).for_each(|_| e.cast_ballot(majority.clone()).unwrap());
+        (0..4).for_each(|_| e.cast_ballot(minority.clone()).unwrap());
+        e
+    }
+
+    #[test]
+    fn test_bloc_star_repeats_star_without_winners() {
+        let result = factional_election().determine_winners(2, MultiWinnerMethod::BlocStar).unwrap();
+        assert_eq!(result.winners, vec!["A", "B"]);
+        assert_eq!(result.rounds[1].runoff.as_ref().map(|r| r.head_to_head), Some((6, 4)));
+        assert!(result.rounds[1].scores.iter().all(|(opt, _)| *opt != "A"));
+    }
+
+    #[test]
+    fn test_allocated_score_reweights_ballots() {
+        let result = factional_election().determine_winners(2, MultiWinnerMethod::AllocatedScore).unwrap();
+        assert_eq!(result.winners, vec!["A", "C"]);
+        // Six ballots at 5 stars share one quota of 5, leaving 1/6 weight on each.
+        assert!((result.rounds[0].weight_remaining - 5.0).abs() < 1e-9);
+        let b = result.rounds[1].scores.iter().find(|(opt, _)| *opt == "B").unwrap().1;
+        assert!((b - 4.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_multi_winner_seat_bounds() {
+        let e = factional_election();
+        assert!(matches!(
+            e.determine_winners(0, MultiWinnerMethod::BlocStar),
+            Err(VotingError::InvalidSeatCount(0))
+        ));
+        assert!(matches!(
+            e.determine_winners(5, MultiWinnerMethod::AllocatedScore),
+            Err(VotingError::InvalidSeatCount(5))
+        ));
+        let full = e.determine_winners(4, MultiWinnerMethod::BlocStar).unwrap();
+        assert_eq!(full.winners, vec!["A", "B", "C", "D"]);
+        assert!(full.rounds[3].runoff.is_none());
+    }
+
+    #[test]
+    fn test_methods_compared_on_same_ballots() {
+        let mut e = election(&["A", "B", "C"]);
+        let groups = [
+            (4, ballot(&[("A", 5), ("B", 3), ("C", 0)])),
+            (3, ballot(&[("A", 0), ("B", 4), ("C", 5)])),
+            (2, ballot(&[("A", 1), ("B", 5), ("C", 1)])),
+        ];
+        for (count, b) in groups {
+            (0..count).for_each(|_| e.cast_ballot(b.clone()).unwrap());
+        }
+
+        let winners: Vec<_> = comparison_methods(e.max_score())
+            .iter()
+            .map(|method| (method.name(), method.winner(&e).unwrap()))
+            .collect();
+        assert_eq!(winners, vec![
+            ("STAR", "B"),
+            ("Score", "B"),
+            ("Approval", "B"),
+            ("Plurality", "A"),
+            ("Ranked Robin", "B"),
+        ]);
+    }
+
+    #[test]
+    fn test_plurality_ignores_flat_ballots() {
+        let mut e = election(&["A", "B"]);
+        e.cast_ballot(ballot(&[("A", 3), ("B", 3)])).unwrap();
+        e.cast_ballot(ballot(&[("A", 1), ("B", 2)])).unwrap();
+        assert_eq!(Plurality.winner(&e).unwrap(), "B");
+    }
+
+    #[test]
+    fn test_custom_score_range() {
+        let mut e = election(&["A", "B", "C"]).with_max_score(10).unwrap();
+        let wide = |scores: &[(&'static str, i8)]| Ballot::with_max_score(scores.iter().cloned(), 10).unwrap();
+        e.cast_ballot(wide(&[("A", 10), ("B", 7), ("C", 0)])).unwrap();
+        e.cast_ballot(wide(&[("A", 0), ("B", 9), ("C", 6)])).unwrap();
+        assert_eq!(e.determine_winner().unwrap().winner, "B");
+
+        assert!(matches!(
+            Ballot::<&str>::with_max_score([("A", 11)], 10),
+            Err(VotingError::InvalidScore(11))
+        ));
+        assert!(matches!(
+            election(&["A", "B"]).with_max_score(11),
+            Err(VotingError::InvalidScoreRange(11))
+        ));
+    }
+
+    #[test]
+    fn test_ballot_above_election_range_rejected() {
+        let mut e = election(&["A", "B"]).with_max_score(3).unwrap();
+        assert!(matches!(
+            e.cast_ballot(ballot(&[("A", 4), ("B", 0)])),
+            Err(VotingError::InvalidScore(4))
+        ));
+        e.cast_ballot(ballot(&[("A", 3), ("B", 1)])).unwrap();
+        assert_eq!(e.determine_winner().unwrap().winner, "A");
+    }
+
+    #[test]
+    fn test_deserialized_negative_score_rejected() {
+        let parsed = serde_json::from_str::<Ballot<String>>(r#"{"scores":{"A":-1,"B":3}}"#);
+        assert!(parsed.is_err());
+
+        let parsed: Ballot<String> = serde_json::from_str(r#"{"scores":{"A":8,"B":3}}"#).unwrap();
+        let mut e = election(&["A".to_string(), "B".to_string()]);
+        assert!(matches!(e.cast_ballot(parsed), Err(VotingError::InvalidScore(8))));
+    }
+
+    #[test]
+    fn test_full_ranking_with_reasons() {
+        let ranking = factional_election().ranking().unwrap();
+        let order: Vec<_> = ranking.iter().map(|place| (place.place, place.option)).collect();
+        assert_eq!(order, vec![(1, "A"), (2, "B"), (3, "C"), (4, "D")]);
+        assert_eq!(ranking[0].reason, PlaceReason::WonRunoff { opponent: "B", votes: (6, 0) });
+        assert_eq!(ranking[2].reason, PlaceReason::WonRunoff { opponent: "D", votes: (4, 0) });
+        assert_eq!(ranking[3].reason, PlaceReason::LastRemaining);
+    }
+
+    #[test]
+    fn test_ranking_records_runoff_tiebreak() {
+        let mut e = election(&["A", "B"]);
+        e.cast_ballot(ballot(&[("A", 5), ("B", 0)])).unwrap();
+        e.cast_ballot(ballot(&[("A", 0), ("B", 4)])).unwrap();
+        let ranking = e.ranking().unwrap();
+        assert_eq!(ranking[0].option, "A");
+        assert_eq!(ranking[0].reason, PlaceReason::RunoffTiebreak { opponent: "B", rule: TiebreakRule::ScoreTotal });
+    }
+
+    #[test]
+    fn test_ranking_marks_unresolved_tie() {
+        let cast = |mut e: Election<&'static str>| {
+            ["A", "B", "C", "D"].into_iter().for_each(|opt| e.add_option(opt).unwrap());
+            e.cast_ballot(ballot(&[("A", 5), ("B", 3), ("C", 3), ("D", 0)])).unwrap();
+            e
+        };
+        let ranking = cast(Election::new()).ranking().unwrap();
+        assert_eq!(ranking[0].option, "A");
+        let rest: Vec<_> = ranking[1..].iter().map(|place| (place.place, place.option)).collect();
+        assert_eq!(rest, vec![(2, "B"), (2, "C"), (2, "D")]);
+        assert!(ranking[1..].iter().all(|place| place.reason == PlaceReason::UnresolvedTie { tied: vec!["B", "C"] }));
+
+        assert!(cast(Election::with_tiebreak_seed(7)).ranking().unwrap().iter().all(|place| !matches!(place.reason, PlaceReason::UnresolvedTie { .. })));
+    }
+
+    #[test]
+    fn test_weighted_ballot_counts_as_copies() {
+        let shareholder = ballot(&[("A", 1), ("B", 4), ("C", 2)]);
+        let others = [
+            ballot(&[("A", 5), ("B", 0), ("C", 3)]),
+            ballot(&[("A", 4), ("B", 1), ("C", 5)]),
+        ];
+
+        let mut weighted = election(&["A", "B", "C"]);
+        let mut copied = election(&["A", "B", "C"]);
+        weighted.cast_ballot(shareholder.clone().with_weight(3)).unwrap();
+        (0..3).for_each(|_| copied.cast_ballot(shareholder.clone()).unwrap());
+        for b in &others {
+            weighted.cast_ballot(b.clone()).unwrap();
+            copied.cast_ballot(b.clone()).unwrap();
+        }
+
+        assert_eq!(weighted.determine_winner().unwrap(), copied.determine_winner().unwrap());
+        assert_eq!(weighted.pairwise_analysis(), copied.pairwise_analysis());
+        assert_eq!(weighted.determine_winner().unwrap().winner, "B");
+    }
+
+    #[test]
+    fn test_total_weight_cannot_overflow_tallies() {
+        let mut e = election(&["A", "B"]);
+        let heavy = ballot(&[("A", 5), ("B", 0)]).with_weight(i32::MAX as u32);
+        e.cast_ballot(heavy.clone()).unwrap();
+        e.cast_ballot(ballot(&[("A", 0), ("B", 5)]).with_weight(2)).unwrap();
+        assert!(matches!(e.cast_ballot(heavy), Err(VotingError::WeightOverflow)));
+        assert_eq!(e.ballot_count(), 2);
+        assert_eq!(e.determine_winner().unwrap().head_to_head, (i32::MAX as u32, 2));
+    }
+
+    #[test]
+    fn prop_incremental_matrix_matches_ballot_scan() {
+        let mut rng = SplitMix64::new(0x4D41_5452);
+        for case in 0..300 {
+            let (options, ballots) = random_case(&mut rng);
+            let mut e = election(&options);
+            ballots.iter().for_each(|b| e.cast_ballot(b.clone()).unwrap());
+
+            let pairwise = e.pairwise_analysis();
+            for (i, a) in options.iter().enumerate() {
+                for (j, b) in options.iter().enumerate() {
+                    let scanned = ballots.iter()
+                        .filter(|ballot| ballot.score(a) > ballot.score(b))
+                        .count() as u32;
+                    assert_eq!(pairwise.matrix[i][j], scanned, "case {case}");
+                }
+            }
+            assert_eq!(e.ballot_count(), ballots.len() as u64);
+        }
+    }
+
+    #[test]
+    fn test_option_added_after_ballots() {
+        // Earlier ballots count as blank for the new option, as if cast without it.
+        for (policy, matrix, votes) in [
+            (BlankPolicy::CountAsZero, vec![vec![0, 1, 1], vec![1, 0, 1], vec![1, 1, 0]], 2),
+            (BlankPolicy::Exclude, vec![vec![0, 1, 0], vec![1, 0, 0], vec![1, 1, 0]], 1),
+        ] {
+            let mut e = election(&["A", "B"]).with_blank_policy(policy);
+            e.cast_ballot(ballot(&[("A", 5), ("B", 1)])).unwrap();
+            e.add_option("C").unwrap();
+            e.cast_ballot(ballot(&[("A", 0), ("B", 2), ("C", 4)])).unwrap();
+            assert_eq!(e.pairwise_analysis().matrix, matrix, "{policy:?}");
+            let totals: Vec<_> = e.option_metrics().map(|(opt, m)| (*opt, m.total())).collect();
+            assert_eq!(totals, vec![("A", 5), ("B", 3), ("C", 4)]);
+            let (_, c) = e.option_metrics().find(|(opt, _)| **opt == "C").unwrap();
+            assert_eq!((c.abstentions(), c.votes()), (1, votes), "{policy:?}");
+
+            let mut fresh = election(&["A", "B", "C"]).with_blank_policy(policy);
+            fresh.cast_ballot(ballot(&[("A", 5), ("B", 1)])).unwrap();
+            fresh.cast_ballot(ballot(&[("A", 0), ("B", 2), ("C", 4)])).unwrap();
+            assert_eq!(e.ballot_groups().collect::<Vec<_>>(), fresh.ballot_groups().collect::<Vec<_>>(), "{policy:?}");
+        }
+    }
+
+    #[test]
+    fn test_blank_policy() {
+        for (policy, matrix, votes) in [
+            (BlankPolicy::CountAsZero, vec![vec![0, 1], vec![1, 0]], 2),
+            (BlankPolicy::Exclude, vec![vec![0, 0], vec![0, 0]], 1),
+        ] {
+            let mut e = election(&["A", "B"]).with_blank_policy(policy);
+            e.cast_ballot(ballot(&[("A", 2)])).unwrap();
+            e.cast_ballot(ballot(&[("B", 1)])).unwrap();
+
+            assert_eq!(e.pairwise_analysis().matrix, matrix, "{policy:?}");
+            for (_, metrics) in e.option_metrics() {
+                assert_eq!(metrics.abstentions(), 1, "{policy:?}");
+                assert_eq!(metrics.votes(), votes, "{policy:?}");
+            }
+            assert_eq!(e.determine_winner().unwrap().winner, "A");
+        }
+    }
+
+    #[test]
+    fn test_ballot_analytics() {
+        let mut e = election(&["A", "B", "C"]);
+        for _ in 0..3 {
+            e.cast_ballot(ballot(&[("A", 5), ("B", 4), ("C", 0)])).unwrap();
+        }
+        e.cast_ballot(ballot(&[("A", 4), ("B", 5), ("C", 1)])).unwrap();
+        e.cast_ballot(ballot(&[("A", 0), ("B", 0), ("C", 5)])).unwrap();
+        e.cast_ballot(ballot(&[("A", 1), ("B", 0), ("C", 5)]).with_weight(3)).unwrap();
+
+        let analytics = analyze(&e);
+        assert_eq!(analytics.options, vec!["A", "B", "C"]);
+        assert!((analytics.bullet_share - 1.0 / 8.0).abs() < 1e-9);
+        assert!((analytics.exaggerated_share - 1.0 / 8.0).abs() < 1e-9);
+        assert!(analytics.correlation[0][1].unwrap() > 0.9);
+        assert!(analytics.correlation[0][2].unwrap() < -0.9);
+        assert!((analytics.correlation[2][2].unwrap() - 1.0).abs() < 1e-9);
+
+        let shares: Vec<_> = analytics.clusters.iter().map(|c| c.share).collect();
+        assert_eq!(shares.len(), 3);
+        assert!((shares.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+        assert!(analytics.clusters[0].centroid.iter().any(|(opt, score)| *opt == "C" && *score > 4.9));
+    }
+
+    #[test]
+    fn test_simulation_is_reproducible() {
+        let config = SimulationConfig {
+            model: "spatial:2".parse().unwrap(),
+            voters: 31,
+            candidates: 4,
+            trials: 60,
+            max_score: 5,
+            seed: 7,
+        };
+        let metrics = simulate(&config).unwrap();
+        assert_eq!(metrics, simulate(&config).unwrap());
+
+        let by_name = |name: &str| metrics.iter().find(|m| m.method == name).unwrap();
+        assert!(by_name("STAR").bayesian_regret <= by_name("Plurality").bayesian_regret);
+        for m in &metrics {
+            assert!(m.bayesian_regret >= 0.0);
+            assert!((0.0..=1.0).contains(&m.condorcet_efficiency));
+            assert!((0.0..=1.0).contains(&m.tie_rate));
+        }
+        assert!("impartial".parse::<VoterModel>().is_ok());
+        assert!("spatial:0".parse::<VoterModel>().is_err());
+    }
+
+    #[test]
+    fn test_verify_ignores_ballot_order() {
+        let mut rng = SplitMix64::new(0x5645_5249);
+        for case in 0..100 {
+            let (options, mut ballots) = random_case(&mut rng);
+            let mut e = Election::with_tiebreak_seed(case);
+            options.iter().for_each(|opt| e.add_option(*opt).unwrap());
+            ballots.iter().for_each(|b| e.cast_ballot(b.clone()).unwrap());
+            let result = e.determine_winner().unwrap();
+
+            ballots.reverse();
+            let mut reordered = Election::with_tiebreak_seed(case);
+            options.iter().for_each(|opt| reordered.add_option(*opt).unwrap());
+            ballots.iter().for_each(|b| reordered.cast_ballot(b.clone()).unwrap());
+            assert_eq!(reordered.verify(&result).unwrap(), vec![], "case {case}");
+        }
+    }
+
+    #[test]
+    fn test_verify_reports_mismatches() {
+        let mut e = election(&["A", "B", "C"]);
+        e.cast_ballot(ballot(&[("A", 5), ("B", 3), ("C", 0)])).unwrap();
+        e.cast_ballot(ballot(&[("A", 4), ("B", 5), ("C", 1)])).unwrap();
+        e.cast_ballot(ballot(&[("A", 5), ("B", 0), ("C", 2)])).unwrap();
+
+        let mut tampered = e.determine_winner().unwrap();
+        tampered.winner = "B";
+        tampered.head_to_head = (1, 2);
+        assert_eq!(e.verify(&tampered).unwrap(), vec![
+            ResultMismatch::Winner { stored: "B", recounted: "A" },
+            ResultMismatch::HeadToHead { stored: (1, 2), recounted: (2, 1) },
+        ]);
+    }
+
+    #[test]
+    fn test_margin_of_victory() {
+        let mut e = election(&["A", "B", "C"]);
+        e.cast_ballot(ballot(&[("A", 5), ("B", 3), ("C", 0)])).unwrap();
+        e.cast_ballot(ballot(&[("A", 4), ("B", 5), ("C", 1)])).unwrap();
+        e.cast_ballot(ballot(&[("A", 5), ("B", 0), ("C", 2)])).unwrap();
+
+        let result = e.determine_winner().unwrap();
+        assert_eq!(e.margin_of_victory(&result).unwrap(), VictoryMargin {
+            runoff_ballots_added: 2,
+            runoff_ballots_changed: 1,
+            finalist_margin: Some(ChallengerMargin {
+                challenger: "C",
+                ballots_added: 2,
+                ballots_changed: Some(1),
+            }),
+        });
+
+        let mut two = election(&["A", "B"]);
+        two.cast_ballot(ballot(&[("A", 5), ("B", 0)])).unwrap();
+        let result = two.determine_winner().unwrap();
+        assert_eq!(two.margin_of_victory(&result).unwrap().finalist_margin, None);
+
+        let stranger = RunoffResult { finalist2: "Z", ..result };
+        assert!(matches!(two.margin_of_victory(&stranger), Err(VotingError::InvalidOption("Z"))));
+    }
+
+    #[test]
+    fn prop_without_options_matches_fresh_count() {
+        let mut rng = SplitMix64::new(0x5749_5448);
+        for case in 0..200 {
+            let (options, ballots) = random_case(&mut rng);
+            if options.len() < 3 {
+                continue;
+            }
+            let removed = options[rng.below(options.len())];
+            let kept: Vec<_> = options.iter().copied().filter(|opt| *opt != removed).collect();
+
+            let mut full = Election::with_tiebreak_seed(case);
+            options.iter().for_each(|opt| full.add_option(*opt).unwrap());
+            ballots.iter().for_each(|b| full.cast_ballot(b.clone()).unwrap());
+            let withdrawn = full.without_options(&[removed]).unwrap();
+
+            let mut fresh = Election::with_tiebreak_seed(case);
+            kept.iter().for_each(|opt| fresh.add_option(*opt).unwrap());
+            for b in &ballots {
+                let scores = b.scores()
+                    .filter(|(opt, _)| **opt != removed)
+                    .map(|(opt, score)| (*opt, score.as_i8()));
+                fresh.cast_ballot(Ballot::new(scores).unwrap()).unwrap();
+            }
+
+            assert_eq!(withdrawn.pairwise_analysis(), fresh.pairwise_analysis(), "case {case}");
+            assert_eq!(withdrawn.ballot_count(), fresh.ballot_count(), "case {case}");
+            assert!(withdrawn.options().all(|opt| *opt != removed));
+            let totals = |e: &Election<&str>| e.option_metrics().map(|(_, m)| m.total()).collect::<Vec<_>>();
+            assert_eq!(totals(&withdrawn), totals(&fresh), "case {case}");
+        }
+    }
+
+    #[test]
+    fn test_without_unknown_option() {
+        let e = election(&["A", "B", "C"]);
+        assert!(matches!(e.without_options(&["D"]), Err(VotingError::InvalidOption("D"))));
+    }
+
+    #[test]
+    fn test_tabulation_outcomes() {
+        let e = election(&["A", "B"]);
+        assert!(matches!(e.determine_winner(), Err(VotingError::InsufficientBallots)));
+
+        let mut e = election(&["A", "B", "C"]);
+        e.cast_ballot(ballot(&[("A", 5), ("B", 5), ("C", 5)])).unwrap();
+        let outcome = TabulationOutcome::try_from(e.determine_winner().unwrap_err()).unwrap();
+        assert_eq!(outcome, TabulationOutcome::FirstPlaceTie { tied: vec!["A", "B", "C"] });
+
+        let mut e = election(&["A", "B", "C"]);
+        e.cast_ballot(ballot(&[("A", 5), ("B", 0), ("C", 0)])).unwrap();
+        e.cast_ballot(ballot(&[("A", 1), ("B", 3), ("C", 3)])).unwrap();
+        e.cast_ballot(ballot(&[("A", 1), ("B", 3), ("C", 3)])).unwrap();
+        let outcome = TabulationOutcome::try_from(e.determine_winner().unwrap_err()).unwrap();
+        assert_eq!(outcome, TabulationOutcome::SecondPlaceTie { tied: vec!["B", "C"] });
+
+        let error = VotingError::InvalidScore(9);
+        assert!(matches!(TabulationOutcome::<&str>::try_from(error), Err(VotingError::InvalidScore(9))));
+        let error = VotingError::InvalidSeatCount(5);
+        assert!(matches!(TabulationOutcome::<&str>::try_from(error), Err(VotingError::InvalidSeatCount(5))));
+    }
+
+    fn ballot_file() -> BallotFile {
+        BallotFile {
+            title: "Lunch, \"quoted\"".into(),
+            options: vec!["Tacos".into(), "Pho, large".into(), "\"Curry\"".into()],
+            seats: 1,
+            max_score: 5,
+            ballots: vec![
+                ballot(&[("Tacos".to_string(), 5), ("Pho, large".to_string(), 3)]).with_weight(2),
+                ballot(&[("Tacos".to_string(), 0), ("Pho, large".to_string(), 4), ("\"Curry\"".to_string(), 5)]),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_ballot_csv_round_trip() {
+        let file = ballot_file();
+        let csv = file.to_csv();
+        assert_eq!(csv, "Tacos,\"Pho, large\",\"\"\"Curry\"\"\"\n5,3,\n5,3,\n0,4,5\n");
+
+        let read = BallotFile::from_csv(&csv, 5).unwrap();
+        assert_eq!(read.options, file.options);
+        assert_eq!(read.ballots.len(), 3, "weighted ballots are written as copies");
+        assert_eq!(read.ballots[0], file.ballots[0].clone().with_weight(1));
+        assert_eq!(read.ballots[2], file.ballots[1]);
+
+        assert_eq!(BallotFile::from_csv("", 5), Err(FormatError::MissingHeader));
+        assert!(matches!(BallotFile::from_csv("A,B\r\n5,3\r\n1\r\n", 5), Err(FormatError::Invalid { line: 3, .. })));
+        assert!(matches!(BallotFile::from_csv("A,B\n5,6\n", 5), Err(FormatError::Invalid { line: 2, .. })));
+        assert!(matches!(BallotFile::from_csv("A,A\n", 5), Err(FormatError::Invalid { line: 1, .. })));
+    }
+
+    #[test]
+    fn test_ballot_csv_keeps_padded_names() {
+        let file = BallotFile {
+            options: vec![" Tacos".into(), "Pho ".into()],
+            ballots: vec![ballot(&[(" Tacos".to_string(), 5), ("Pho ".to_string(), 2)])],
+            ..ballot_file()
+        };
+        let csv = file.to_csv();
+        assert_eq!(csv, "\" Tacos\",\"Pho \"\n5,2\n");
+        assert_eq!(BallotFile::from_csv(&csv, 5).unwrap().ballots, file.ballots);
+        assert_eq!(BallotFile::from_csv(" A , B \n 1 , 2 \n", 5).unwrap().options, ["A", "B"]);
+    }
+
+    #[test]
+    fn test_ballot_blt_round_trip() {
+        let file = ballot_file();
+        let blt = file.to_blt();
+        assert!(blt.starts_with("3 1 5\n2 5 3 - 0\n1 0 4 5 0\n0\n\"Tacos\"\n"));
+
+        let read = BallotFile::from_blt(&blt).unwrap();
+        assert_eq!(read, file);
+
+        let mut e = election(&read.options);
+        read.ballots.iter().for_each(|b| e.cast_ballot(b.clone()).unwrap());
+        assert_eq!(e.determine_winner().unwrap().winner, "Tacos");
+
+        assert!(matches!(BallotFile::from_blt("2 1\n1 5 0\n0\n\"A\"\n\"B\"\n"), Err(FormatError::Invalid { line: 2, .. })));
+        assert!(matches!(BallotFile::from_blt("2 1\n1 5 3\n0\n\"A\"\n\"B\"\n"), Err(FormatError::Invalid { line: 2, .. })));
+        assert!(matches!(BallotFile::from_blt("2 1\n1 5 3 0\n"), Err(FormatError::Invalid { line: 1, .. })));
+    }
+
+    #[test]
+    fn test_ballot_blt_escapes_line_breaks() {
+        let file = BallotFile {
+            title: "Lunch\r\npoll".into(),
+            options: vec!["Tacos\nal pastor".into(), "C:\\Pho".into()],
+            ballots: vec![ballot(&[("Tacos\nal pastor".to_string(), 5)])],
+            ..ballot_file()
+        };
+        let blt = file.to_blt();
+        assert!(blt.ends_with("0\n\"Tacos\\nal pastor\"\n\"C:\\\\Pho\"\n\"Lunch\\r\\npoll\"\n"));
+        assert_eq!(BallotFile::from_blt(&blt).unwrap(), file);
+        assert!(matches!(BallotFile::from_blt("1 1\n0\n\"A\\t\"\n"), Err(FormatError::Invalid { line: 3, .. })));
+    }
+
+    #[test]
+    fn test_ballot_blt_header_checked() {
+        let names = "0\n\"A\"\n\"B\"\n";
+        for header in ["2 1 0", "2 1 11", "2 1 -1", "2 0 5", "2 3 5"] {
+            let blt = format!("{header}\n{names}");
+            assert!(matches!(BallotFile::from_blt(&blt), Err(FormatError::Invalid { line: 1, .. })), "{header}");
+        }
+        assert!(BallotFile::from_blt(&format!("2 2 10\n{names}")).is_ok());
+    }
+
+    #[test]
+    fn test_ballot_file_written_a_row_at_a_time() {
+        let file = ballot_file();
+        let header = BallotFile { ballots: Vec::new(), ..ballot_file() };
+        for (format, whole) in [(BallotFormat::Csv, file.to_csv()), (BallotFormat::Blt, file.to_blt())] {
+            let mut rows = header.header(format);
+            file.ballots.iter().for_each(|b| rows.push_str(&header.row(format, b)));
+            rows.push_str(&header.footer(format));
+            assert_eq!(rows, whole);
+        }
+    }
+
+    #[test]
+    fn test_final_stats_without_ranking() {
+        let stored = r#"{"totalBallots":2,"optionScores":{"A":{"totalScore":9,"averageScore":4.5,"frequency":{"4":1,"5":1},"totalVotes":2}}}"#;
+        let value: serde_json::Value = serde_json::from_str(stored).unwrap();
+        let (stats, ranking) = serde_json::from_value::<FinalStats>(value).unwrap().into_parts();
+        assert_eq!(stats.total_ballots, 2);
+        assert_eq!(stats.option_scores["A"].frequency[&5], 1);
+        assert!(ranking.is_none());
+
+        let archived = serde_json::to_value(FinalStats::new(stats, Vec::new())).unwrap();
+        let (stats, ranking) = serde_json::from_value::<FinalStats>(archived).unwrap().into_parts();
+        assert_eq!(stats.option_scores["A"].total_score, 9);
+        assert_eq!(ranking, Some(Vec::new()));
+    }
+
+    #[test]
+    fn test_results_visibility_round_trip() {
+        for visibility in [ResultsVisibility::HiddenUntilClose, ResultsVisibility::Live, ResultsVisibility::CreatorOnly] {
+            assert_eq!(visibility.as_str().parse(), Ok(visibility));
+        }
+        assert_eq!(ResultsVisibility::default(), ResultsVisibility::HiddenUntilClose);
+        assert!("public".parse::<ResultsVisibility>().is_err());
+    }
+
+    #[test]
+    fn test_provisional_results_shown_by_visibility() {
+        for is_creator in [false, true] {
+            assert!(!ResultsVisibility::HiddenUntilClose.shows_provisional(is_creator));
+            assert!(ResultsVisibility::Live.shows_provisional(is_creator));
+            assert_eq!(ResultsVisibility::CreatorOnly.shows_provisional(is_creator), is_creator);
+        }
+    }
+
+    #[test]
+    fn test_vote_opening_and_end() {
+        let now = OffsetDateTime::now_utc();
+        let mut vote = Vote {
+            id: Uuid::nil(),
+            title: "Lunch".into(),
+            description: String::new(),
+            options: vec!["A".into(), "B".into()],
+            voting_ends_at: now + Duration::hours(2),
+            ballot_count: 0,
+            duration_hours: 1,
+            duration_minutes: 0,
+            user_fingerprint: String::new(),
+            seats: 1,
+            winner_method: MultiWinnerMethod::default(),
+            max_score: 5,
+            weighted: false,
+            blank_policy: BlankPolicy::default(),
+            withdrawn_options: Vec::new(),
+            results_visibility: ResultsVisibility::default(),
+            opens_at: None,
+            visibility: VoteVisibility::default(),
+            tiebreak_seed: None,
+        };
+        assert!(vote.has_opened_at(now));
+        assert!(!vote.is_ended_at(now));
+
+        vote.opens_at = Some(now + Duration::hours(1));
+        assert!(!vote.has_opened_at(now));
+        assert!(vote.has_opened_at(now + Duration::hours(1)));
+        assert!(!vote.is_ended_at(now + Duration::hours(2)));
+        assert!(vote.is_ended_at(now + Duration::hours(2) + Duration::seconds(1)));
+    }
+
+    #[test]
+    fn test_vote_visibility_round_trip() {
+        for visibility in [VoteVisibility::Public, VoteVisibility::Unlisted, VoteVisibility::Private] {
+            assert_eq!(visibility.as_str().parse(), Ok(visibility));
+        }
+        assert_eq!(VoteVisibility::default(), VoteVisibility::Public);
+        assert!("hidden".parse::<VoteVisibility>().is_err());
+    }
+}
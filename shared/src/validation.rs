@@ -1,5 +1,6 @@
 use std::collections::HashSet;
 use crate::models::{CreateVoteRequest, VoteBallot};
+use crate::star_logic::MAX_SCORE_LIMIT;
 
 pub const MAX_TITLE_LENGTH: usize = 100;
 pub const MAX_DESCRIPTION_LENGTH: usize = 500;
@@ -10,7 +11,6 @@ pub const MAX_DURATION_HOURS: i32 = 23;
 pub const MAX_DURATION_MINUTES: i32 = 59;
 pub const MIN_OPTIONS: usize = 2;
 pub const MIN_SCORE: i8 = 0;
-pub const MAX_SCORE: i8 = 5;
 
 #[derive(Debug, thiserror::Error)]
 pub enum ValidationError {
@@ -28,14 +28,18 @@ pub enum ValidationError {
     DurationTooLong,
     #[error("Duration must be at least 1 minute")]
     DurationTooShort,
-    #[error("Invalid score: {0} (must be {MIN_SCORE}-{MAX_SCORE})")]
-    InvalidScore(i8),
+    #[error("Invalid score: {0} (must be {MIN_SCORE}-{1})")]
+    InvalidScore(i8, i32),
+    #[error("Maximum score must be between 1 and {MAX_SCORE_LIMIT}")]
+    InvalidScoreRange,
     #[error("Duplicate option: {0}")]
     DuplicateOption(String),
     #[error("Empty option text")]
     EmptyOption,
     #[error("Invalid ballot: {0}")]
     InvalidBallot(String),
+    #[error("Seat count must be between 1 and the number of options")]
+    InvalidSeatCount,
 }
 
 pub fn validate_vote_request(request: &CreateVoteRequest) -> Result<(), ValidationError> {
@@ -63,6 +67,8 @@ pub fn validate_vote_request(request: &CreateVoteRequest) -> Result<(), Validati
 
     if request.options.iter().any(|opt| opt.is_empty()) { return Err(ValidationError::EmptyOption); }
     if request.options.iter().any(|opt| opt.len() > MAX_OPTION_LENGTH) { return Err(ValidationError::OptionTooLong); }
+    if request.seats < 1 || request.seats as usize > request.options.len() { return Err(ValidationError::InvalidSeatCount); }
+    if !(1..=i32::from(MAX_SCORE_LIMIT)).contains(&request.max_score) { return Err(ValidationError::InvalidScoreRange); }
 
     let (total_hours, total_minutes) = (request.duration_hours, request.duration_minutes);
     if total_hours == 0 && total_minutes == 0 { return Err(ValidationError::DurationTooShort); }
@@ -77,9 +83,10 @@ pub fn validate_vote_request(request: &CreateVoteRequest) -> Result<(), Validati
     Ok(())
 }
 
-pub fn validate_ballot(ballot: &VoteBallot, options: &[String]) -> Result<(), ValidationError> {
-    if let Some(&invalid_score) = ballot.scores.values().find(|&&score| score < MIN_SCORE || score > MAX_SCORE) {
-        return Err(ValidationError::InvalidScore(invalid_score));
+pub fn validate_ballot(ballot: &VoteBallot, options: &[String], max_score: i32) -> Result<(), ValidationError> {
+    let range = i32::from(MIN_SCORE)..=max_score;
+    if let Some(invalid_score) = ballot.scores.values().flatten().copied().find(|&score| !range.contains(&i32::from(score))) {
+        return Err(ValidationError::InvalidScore(invalid_score, max_score));
     }
 
     let invalid_option = ballot.scores.keys().find(|opt| !options.contains(opt));
@@ -87,9 +94,8 @@ pub fn validate_ballot(ballot: &VoteBallot, options: &[String]) -> Result<(), Va
         return Err(ValidationError::InvalidBallot(format!("Invalid option: {}", opt)));
     }
 
-    let missing_option = options.iter().find(|opt| !ballot.scores.contains_key(*opt));
-    if let Some(opt) = missing_option {
-        return Err(ValidationError::InvalidBallot(format!("Missing score for option: {}", opt)));
+    if ballot.scores.values().all(Option::is_none) {
+        return Err(ValidationError::InvalidBallot("Ballot must score at least one option".into()));
     }
 
     Ok(())
@@ -0,0 +1,1251 @@
+//! STAR voting tabulation: ballots, elections, tiebreaks and multi-winner
+//! methods, with no I/O. Builds without `std` (only `alloc`) when the default
+//! `std` feature is swapped for `alloc`.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(not(any(feature = "std", feature = "alloc")))]
+compile_error!("star-core needs either the `std` or the `alloc` feature");
+
+extern crate alloc;
+
+use alloc::{boxed::Box, format, string::String, vec, vec::Vec};
+use core::hash::{BuildHasher, Hash};
+use core::cmp::{Ordering, Reverse};
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+#[cfg(all(not(feature = "std"), feature = "alloc"))]
+use hashbrown::HashMap;
+use thiserror::Error;
+use serde::{de, Deserializer, Serialize, Deserialize};
+
+const RUNNER_UP_MATCHUPS: usize = 3;
+pub const DEFAULT_MAX_SCORE: i8 = 5;
+pub const MAX_SCORE_LIMIT: i8 = 10;
+
+#[derive(Error, Debug, Clone)]
+pub enum VotingError<T> {
+    #[error("Invalid score {0}. Outside the election's score range")] InvalidScore(i8),
+    #[error("Invalid maximum score {0}. Must be between 1 and {MAX_SCORE_LIMIT}")] InvalidScoreRange(i8),
+    #[error("Invalid option: {0:?}")] InvalidOption(T),
+    #[error("Duplicate option: {0:?}")] DuplicateOption(T),
+    #[error("Need at least 2 options")] InsufficientOptions,
+    #[error("No ballots cast")] InsufficientBallots,
+    #[error("Perfect tie for first between {0:?}")] FirstPlaceTie(Vec<T>),
+    #[error("Tie for second between {0:?}")] SecondPlaceTie(Vec<T>),
+    #[error("Invalid seat count {0}")] InvalidSeatCount(usize),
+    #[error("Total ballot weight cannot exceed {}", u32::MAX)] WeightOverflow,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+pub struct Score(i8);
+
+impl Score {
+    pub fn new(value: i8, max_score: i8) -> Result<Self, i8> {
+        if (0..=max_score.min(MAX_SCORE_LIMIT)).contains(&value) {
+            Ok(Score(value))
+        } else {
+            Err(value)
+        }
+    }
+
+    pub const fn as_i8(self) -> i8 { 
+        self.0 
+    }
+}
+
+impl TryFrom<i8> for Score {
+    type Error = i8;
+    
+    fn try_from(v: i8) -> Result<Self, i8> {
+        Score::new(v, DEFAULT_MAX_SCORE)
+    }
+}
+
+/// Deserialized scores are only checked against `MAX_SCORE_LIMIT`, since the
+/// election's own range is not known yet; `Election::cast_ballot` narrows it.
+impl<'de> Deserialize<'de> for Score {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = i8::deserialize(deserializer)?;
+        Score::new(value, MAX_SCORE_LIMIT)
+            .map_err(|v| de::Error::custom(format_args!("invalid score {v}, expected 0 to {MAX_SCORE_LIMIT}")))
+    }
+}
+
+/// Options missing from a ballot are blank ("no opinion"); the election's
+/// `BlankPolicy` decides whether they count as zero or are left out.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Ballot<T: Clone + Eq + Hash> {
+    scores: HashMap<T, Score>,
+    #[serde(default = "unit_weight")]
+    weight: u32,
+}
+
+fn unit_weight() -> u32 { 1 }
+
+impl<T: Clone + Eq + Hash> Default for Ballot<T> {
+    fn default() -> Self { Self { scores: HashMap::new(), weight: 1 } }
+}
+
+impl<T: Clone + Eq + Hash> Ballot<T> {
+    /// Takes any `(option, score)` pairs rather than a map, so callers are not
+    /// tied to whichever `HashMap` the enabled features select.
+    pub fn new(scores: impl IntoIterator<Item = (T, i8)>) -> Result<Self, VotingError<T>> {
+        Self::with_max_score(scores, DEFAULT_MAX_SCORE)
+    }
+
+    pub fn with_max_score(scores: impl IntoIterator<Item = (T, i8)>, max_score: i8) -> Result<Self, VotingError<T>> {
+        scores.into_iter()
+            .map(|(k, v)| Score::new(v, max_score)
+                .map(|score| (k, score))
+                .map_err(VotingError::InvalidScore))
+            .collect::<Result<HashMap<_, _>, _>>()
+            .map(|scores| Self { scores, weight: 1 })
+    }
+
+    pub fn with_weight(self, weight: u32) -> Self {
+        Self { weight, ..self }
+    }
+    
+    pub fn scores(&self) -> impl Iterator<Item = (&T, Score)> + '_ {
+        self.scores.iter().map(|(option, &score)| (option, score))
+    }
+
+    pub fn score(&self, option: &T) -> Option<Score> { self.scores.get(option).copied() }
+
+    pub fn weight(&self) -> u32 { self.weight }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScoreMetrics {
+    total: i64,
+    by_value: Vec<u32>,
+    abstentions: u32,
+}
+
+impl ScoreMetrics {
+    fn record(&mut self, score: Score, weight: u32) {
+        let index = score.as_i8() as usize;
+        if self.by_value.len() <= index {
+            self.by_value.resize(index + 1, 0);
+        }
+        self.total += i64::from(score.as_i8()) * i64::from(weight);
+        self.by_value[index] += weight;
+    }
+
+    fn record_blank(&mut self, weight: u32) {
+        self.abstentions += weight;
+    }
+
+    pub fn total(&self) -> i64 { self.total }
+
+    pub fn abstentions(&self) -> u32 { self.abstentions }
+
+    pub fn count(&self, score: i8) -> u32 {
+        self.by_value.get(score as usize).copied().unwrap_or(0)
+    }
+
+    pub fn votes(&self) -> u64 {
+        self.by_value.iter().map(|&n| u64::from(n)).sum()
+    }
+
+    fn count_at_least(&self, score: i8) -> u32 {
+        self.by_value.iter().skip(score as usize).sum()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VotingOption<T> {
+    value: T,
+    metrics: ScoreMetrics,
+    order: u64
+}
+
+impl<T: Clone + Eq + Hash> VotingOption<T> {
+    fn new(value: T, order: u64) -> Self {
+        Self { value, metrics: ScoreMetrics::default(), order }
+    }
+    pub fn value(&self) -> &T { &self.value }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HeadToHeadMatchup<T> {
+    pub candidate1: T,
+    pub candidate2: T,
+    pub votes1: u32,
+    pub votes2: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PairwiseAnalysis<T> {
+    pub options: Vec<T>,
+    pub matrix: Vec<Vec<u32>>,
+    pub condorcet_winner: Option<T>,
+    pub condorcet_loser: Option<T>,
+    pub smith_set: Vec<T>,
+}
+
+impl<T> PairwiseAnalysis<T> {
+    pub fn beats(&self, i: usize, j: usize) -> bool {
+        self.matrix[i][j] > self.matrix[j][i]
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TiebreakStage { FirstFinalist, SecondFinalist, Runoff }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TiebreakRule {
+    HeadToHead,
+    ScoreTotal,
+    TopScoreCount,
+    RandomLot,
+    DecidedByRunoff,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TiebreakStep<T> {
+    pub stage: TiebreakStage,
+    pub rule: TiebreakRule,
+    pub candidates: Vec<(T, u64)>,
+    pub advanced: Vec<T>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TiebreakTrace<T> {
+    pub steps: Vec<TiebreakStep<T>>,
+}
+
+impl<T> Default for TiebreakTrace<T> {
+    fn default() -> Self { Self { steps: Vec::new() } }
+}
+
+impl<T> TiebreakTrace<T> {
+    pub fn is_empty(&self) -> bool { self.steps.is_empty() }
+
+    pub fn stage(&self, stage: TiebreakStage) -> impl Iterator<Item = &TiebreakStep<T>> {
+        self.steps.iter().filter(move |step| step.stage == stage)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RunoffResult<T> {
+    pub winner: T,
+    pub finalist1: T,
+    pub finalist2: T,
+    pub head_to_head: (u32, u32),
+    pub runner_up_matchups: Vec<HeadToHeadMatchup<T>>,
+    pub tiebreak: TiebreakTrace<T>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VictoryMargin<T> {
+    /// New ballots preferring the runoff loser that would have elected it.
+    pub runoff_ballots_added: u64,
+    /// Existing ballots that would have to switch to the runoff loser.
+    pub runoff_ballots_changed: u64,
+    /// The option closest to a runoff spot; `None` with only two options.
+    pub finalist_margin: Option<ChallengerMargin<T>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChallengerMargin<T> {
+    pub challenger: T,
+    /// New ballots giving the challenger the top score and everyone else 0.
+    pub ballots_added: u64,
+    /// Existing ballots to rewrite; `None` if no rewrite of these ballots is enough.
+    pub ballots_changed: Option<u64>,
+}
+
+/// One way a stored result disagrees with a fresh count of the same ballots.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "field", rename_all = "camelCase")]
+pub enum ResultMismatch<T> {
+    Winner { stored: T, recounted: T },
+    Finalists { stored: (T, T), recounted: (T, T) },
+    HeadToHead { stored: (u32, u32), recounted: (u32, u32) },
+    RunnerUpMatchups { stored: Vec<HeadToHeadMatchup<T>>, recounted: Vec<HeadToHeadMatchup<T>> },
+    Tiebreak { stored: TiebreakTrace<T>, recounted: TiebreakTrace<T> },
+}
+
+/// How tabulation ended, for clients that need to tell the cases apart
+/// without matching on error text.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum TabulationOutcome<T> {
+    Winner,
+    FirstPlaceTie { tied: Vec<T> },
+    SecondPlaceTie { tied: Vec<T> },
+    InsufficientBallots,
+    InsufficientOptions,
+}
+
+impl<T> TryFrom<VotingError<T>> for TabulationOutcome<T> {
+    type Error = VotingError<T>;
+
+    /// Errors that don't describe an outcome, such as an invalid score or seat
+    /// count, are handed back.
+    fn try_from(error: VotingError<T>) -> Result<Self, VotingError<T>> {
+        match error {
+            VotingError::FirstPlaceTie(tied) => Ok(TabulationOutcome::FirstPlaceTie { tied }),
+            VotingError::SecondPlaceTie(tied) => Ok(TabulationOutcome::SecondPlaceTie { tied }),
+            VotingError::InsufficientBallots => Ok(TabulationOutcome::InsufficientBallots),
+            VotingError::InsufficientOptions => Ok(TabulationOutcome::InsufficientOptions),
+            error => Err(error),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MultiWinnerMethod {
+    #[default]
+    BlocStar,
+    AllocatedScore,
+}
+
+impl MultiWinnerMethod {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            MultiWinnerMethod::BlocStar => "bloc_star",
+            MultiWinnerMethod::AllocatedScore => "allocated_score",
+        }
+    }
+}
+
+impl core::str::FromStr for MultiWinnerMethod {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            "bloc_star" => Ok(MultiWinnerMethod::BlocStar),
+            "allocated_score" => Ok(MultiWinnerMethod::AllocatedScore),
+            other => Err(format!("Unknown multi-winner method: {other}")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BlankPolicy {
+    #[default]
+    CountAsZero,
+    Exclude,
+}
+
+impl BlankPolicy {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            BlankPolicy::CountAsZero => "count_as_zero",
+            BlankPolicy::Exclude => "exclude",
+        }
+    }
+}
+
+impl core::str::FromStr for BlankPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            "count_as_zero" => Ok(BlankPolicy::CountAsZero),
+            "exclude" => Ok(BlankPolicy::Exclude),
+            other => Err(format!("Unknown blank policy: {other}")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WinnerRound<T> {
+    pub winner: T,
+    pub scores: Vec<(T, f64)>,
+    pub runoff: Option<RunoffResult<T>>,
+    pub tiebreak: TiebreakTrace<T>,
+    pub weight_remaining: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MultiWinnerResult<T> {
+    pub method: MultiWinnerMethod,
+    pub winners: Vec<T>,
+    pub rounds: Vec<WinnerRound<T>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum PlaceReason<T> {
+    WonRunoff { opponent: T, votes: (u32, u32) },
+    RunoffTiebreak { opponent: T, rule: TiebreakRule },
+    LastRemaining,
+    /// No tiebreak settled the tie between `tied`, so this option and every
+    /// other one not yet placed share the place.
+    UnresolvedTie { tied: Vec<T> },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RankedPlace<T> {
+    pub place: usize,
+    pub option: T,
+    pub reason: PlaceReason<T>,
+}
+
+// Identical ballots collapse into one group, so memory grows with distinct ballots rather
+// than with turnout. Scores are indexed by option position.
+#[derive(Debug, Clone)]
+struct BallotGroup {
+    scores: Vec<Option<Score>>,
+    weight: u64,
+    // The next group whose scores hash the same, if any.
+    next: Option<usize>,
+}
+
+impl BallotGroup {
+    fn score(&self, position: usize) -> Option<Score> {
+        self.scores.get(position).copied().flatten()
+    }
+}
+
+#[derive(Debug)]
+pub struct Election<T: Clone + Eq + Hash + Ord> {
+    options: Vec<VotingOption<T>>,
+    positions: HashMap<T, usize>,
+    preferences: Vec<Vec<u32>>,
+    groups: Vec<BallotGroup>,
+    // The first group for each hash of a score vector, so the vectors
+    // themselves are only ever stored in `groups`.
+    group_index: HashMap<u64, usize>,
+    ballot_count: u64,
+    // Bounds every u32 tally, so keeping it within u32 keeps them from overflowing.
+    total_weight: u64,
+    option_order: u64,
+    tiebreak_seed: Option<u64>,
+    max_score: i8,
+    blank_policy: BlankPolicy,
+}
+
+/// The SplitMix64 generator: tiny and fully specified, so anything drawn from
+/// a given seed is identical on every platform. Not for secrets.
+#[derive(Debug, Clone)]
+pub struct SplitMix64(u64);
+
+impl SplitMix64 {
+    const GAMMA: u64 = 0x9E37_79B9_7F4A_7C15;
+
+    pub const fn new(seed: u64) -> Self { Self(seed) }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(Self::GAMMA);
+        Self::mix(self.0)
+    }
+
+    /// Uniform in `[0, 1)`.
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// In `0..n`, with a modulo bias too small to matter for small `n`.
+    pub fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+
+    /// The generator's output finalizer on its own.
+    pub const fn mix(mut z: u64) -> u64 {
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
+
+fn lot_draw(seed: u64, order: u64) -> u64 {
+    SplitMix64::mix(seed ^ order.wrapping_add(1).wrapping_mul(SplitMix64::GAMMA))
+}
+
+impl<T: Clone + Eq + Hash + Ord> Default for Election<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone + Eq + Hash + Ord> Election<T> {
+    pub fn new() -> Self {
+        Self {
+            options: Vec::new(),
+            positions: HashMap::new(),
+            preferences: Vec::new(),
+            groups: Vec::new(),
+            group_index: HashMap::new(),
+            ballot_count: 0,
+            total_weight: 0,
+            option_order: 0,
+            tiebreak_seed: None,
+            max_score: DEFAULT_MAX_SCORE,
+            blank_policy: BlankPolicy::default(),
+        }
+    }
+
+    pub fn with_tiebreak_seed(seed: u64) -> Self {
+        Self { tiebreak_seed: Some(seed), ..Self::new() }
+    }
+
+    pub fn with_max_score(self, max_score: i8) -> Result<Self, VotingError<T>> {
+        if !(1..=MAX_SCORE_LIMIT).contains(&max_score) {
+            return Err(VotingError::InvalidScoreRange(max_score));
+        }
+        Ok(Self { max_score, ..self })
+    }
+
+    pub fn max_score(&self) -> i8 { self.max_score }
+
+    pub fn with_blank_policy(self, blank_policy: BlankPolicy) -> Self {
+        Self { blank_policy, ..self }
+    }
+
+    pub fn blank_policy(&self) -> BlankPolicy { self.blank_policy }
+
+    pub fn add_option(&mut self, option: T) -> Result<(), VotingError<T>> {
+        if self.positions.contains_key(&option) {
+            return Err(VotingError::DuplicateOption(option));
+        }
+        let position = self.options.len();
+        self.positions.insert(option.clone(), position);
+        self.options.push(VotingOption::new(option, self.option_order));
+        self.preferences.iter_mut().for_each(|row| row.push(0));
+        self.preferences.push(vec![0; self.options.len()]);
+        self.option_order += 1;
+        self.backfill_blank(position);
+        Ok(())
+    }
+
+    // Ballots cast before the option at `position` existed left it blank, so
+    // they are counted that way, under the blank policy like any other blank.
+    fn backfill_blank(&mut self, position: usize) {
+        let blank = (self.blank_policy == BlankPolicy::CountAsZero).then_some(Score(0));
+        let groups = core::mem::take(&mut self.groups);
+        self.group_index.clear();
+        self.total_weight = 0;
+        for mut group in groups {
+            // Fits, as the total weight of every group does.
+            let weight = group.weight as u32;
+            self.options[position].metrics.record_blank(weight);
+            if let Some(blank) = blank {
+                self.options[position].metrics.record(blank, weight);
+                for (i, score) in group.scores.iter().enumerate() {
+                    if score.is_some_and(|score| score > blank) {
+                        self.preferences[i][position] += weight;
+                    }
+                }
+            }
+            group.scores.push(blank);
+            self.add_group(group.scores, group.weight);
+        }
+    }
+
+    pub fn options(&self) -> impl Iterator<Item = &T> {
+        self.options.iter().map(VotingOption::value)
+    }
+
+    pub fn option_metrics(&self) -> impl Iterator<Item = (&T, &ScoreMetrics)> {
+        self.options.iter().map(|opt| (opt.value(), &opt.metrics))
+    }
+
+    pub fn ballot_count(&self) -> u64 { self.ballot_count }
+
+    pub fn cast_ballot(&mut self, ballot: Ballot<T>) -> Result<(), VotingError<T>> {
+        if let Some(option) = ballot.scores.keys().find(|opt| !self.positions.contains_key(*opt)) {
+            return Err(VotingError::InvalidOption(option.clone()));
+        }
+        if let Some(score) = ballot.scores.values().find(|score| !(0..=self.max_score).contains(&score.as_i8())) {
+            return Err(VotingError::InvalidScore(score.as_i8()));
+        }
+
+        let weight = ballot.weight();
+        if self.total_weight + u64::from(weight) > u64::from(u32::MAX) {
+            return Err(VotingError::WeightOverflow);
+        }
+        let mut scores: Vec<_> = self.options.iter()
+            .map(|opt| ballot.score(opt.value()))
+            .collect();
+        for (i, score) in scores.iter_mut().enumerate().filter(|(_, score)| score.is_none()) {
+            self.options[i].metrics.record_blank(weight);
+            if self.blank_policy == BlankPolicy::CountAsZero {
+                *score = Some(Score(0));
+            }
+        }
+        for (i, si) in scores.iter().enumerate() {
+            let Some(si) = si else { continue };
+            self.options[i].metrics.record(*si, weight);
+            for (j, sj) in scores.iter().enumerate().skip(i + 1) {
+                match sj.map(|sj| si.cmp(&sj)) {
+                    Some(Ordering::Greater) => self.preferences[i][j] += weight,
+                    Some(Ordering::Less) => self.preferences[j][i] += weight,
+                    _ => {}
+                }
+            }
+        }
+
+        self.add_group(scores, u64::from(weight));
+        self.ballot_count += 1;
+        Ok(())
+    }
+
+    fn add_group(&mut self, scores: Vec<Option<Score>>, weight: u64) {
+        self.total_weight += weight;
+        let hash = self.group_index.hasher().hash_one(&scores);
+        let mut last = None;
+        let mut next = self.group_index.get(&hash).copied();
+        while let Some(group) = next {
+            if self.groups[group].scores == scores {
+                self.groups[group].weight += weight;
+                return;
+            }
+            last = Some(group);
+            next = self.groups[group].next;
+        }
+
+        let group = self.groups.len();
+        match last {
+            Some(last) => self.groups[last].next = Some(group),
+            None => { self.group_index.insert(hash, group); }
+        }
+        self.groups.push(BallotGroup { scores, weight, next: None });
+    }
+
+    /// The same ballots tabulated as if `removed` had never been on them. Every
+    /// other tally, including each option's place in the lot order, is kept.
+    pub fn without_options(&self, removed: &[T]) -> Result<Self, VotingError<T>> {
+        if let Some(option) = removed.iter().find(|opt| !self.positions.contains_key(*opt)) {
+            return Err(VotingError::InvalidOption(option.clone()));
+        }
+
+        let kept: Vec<usize> = (0..self.options.len())
+            .filter(|&i| !removed.contains(self.options[i].value()))
+            .collect();
+        let mut election = Self {
+            options: kept.iter().map(|&i| self.options[i].clone()).collect(),
+            positions: kept.iter().enumerate()
+                .map(|(position, &i)| (self.options[i].value().clone(), position))
+                .collect(),
+            preferences: kept.iter()
+                .map(|&i| kept.iter().map(|&j| self.preferences[i][j]).collect())
+                .collect(),
+            groups: Vec::new(),
+            group_index: HashMap::new(),
+            ballot_count: self.ballot_count,
+            total_weight: 0,
+            option_order: self.option_order,
+            tiebreak_seed: self.tiebreak_seed,
+            max_score: self.max_score,
+            blank_policy: self.blank_policy,
+        };
+        for group in &self.groups {
+            election.add_group(kept.iter().map(|&i| group.score(i)).collect(), group.weight);
+        }
+        Ok(election)
+    }
+
+    fn get_head_to_head_votes(&self, option1: &T, option2: &T) -> (u32, u32) {
+        let (i, j) = (self.positions[option1], self.positions[option2]);
+        (self.preferences[i][j], self.preferences[j][i])
+    }
+
+    fn remaining_options(&self, excluded: &[T]) -> Vec<&VotingOption<T>> {
+        self.options.iter().filter(|opt| !excluded.contains(opt.value())).collect()
+    }
+
+    fn sort_options_by_score(&self, excluded: &[T]) -> Result<Vec<&VotingOption<T>>, VotingError<T>> {
+        let mut sorted = self.remaining_options(excluded);
+        if sorted.is_empty() {
+            return Err(VotingError::InsufficientOptions);
+        }
+
+        sorted.sort_by_key(|opt| Reverse(opt.metrics.total));
+        Ok(sorted)
+    }
+
+    fn narrow<'a>(
+        trace: &mut TiebreakTrace<T>,
+        stage: TiebreakStage,
+        rule: TiebreakRule,
+        pool: Vec<&'a VotingOption<T>>,
+        measure: impl Fn(&VotingOption<T>) -> u64,
+    ) -> Vec<&'a VotingOption<T>> {
+        let measured: Vec<_> = pool.into_iter().map(|opt| (opt, measure(opt))).collect();
+        let best = measured.iter().map(|(_, v)| *v).max().unwrap_or(0);
+        let advanced: Vec<_> = measured.iter()
+            .filter(|(_, v)| *v == best)
+            .map(|(opt, _)| *opt)
+            .collect();
+        trace.steps.push(TiebreakStep {
+            stage,
+            rule,
+            candidates: measured.iter().map(|(opt, v)| (opt.value().clone(), *v)).collect(),
+            advanced: advanced.iter().map(|opt| opt.value().clone()).collect(),
+        });
+        advanced
+    }
+
+    fn draw_lot<'a>(
+        &self,
+        trace: &mut TiebreakTrace<T>,
+        stage: TiebreakStage,
+        pool: Vec<&'a VotingOption<T>>,
+    ) -> Option<&'a VotingOption<T>> {
+        let seed = self.tiebreak_seed?;
+        Self::narrow(trace, stage, TiebreakRule::RandomLot, pool, |opt| lot_draw(seed, opt.order))
+            .into_iter()
+            .next()
+    }
+
+    fn tie_error(stage: TiebreakStage, pool: &[&VotingOption<T>]) -> VotingError<T> {
+        let tied = pool.iter().map(|opt| opt.value().clone()).collect();
+        match stage {
+            TiebreakStage::SecondFinalist => VotingError::SecondPlaceTie(tied),
+            _ => VotingError::FirstPlaceTie(tied),
+        }
+    }
+
+    fn break_scoring_tie<'a>(
+        &self,
+        trace: &mut TiebreakTrace<T>,
+        stage: TiebreakStage,
+        tied: &[&'a VotingOption<T>],
+        leader: Option<&VotingOption<T>>,
+    ) -> Result<&'a VotingOption<T>, VotingError<T>> {
+        let pool = Self::narrow(trace, stage, TiebreakRule::HeadToHead, tied.to_vec(), |opt| {
+            tied.iter()
+                .filter(|other| other.value() != opt.value())
+                .filter(|other| {
+                    let (won, lost) = self.get_head_to_head_votes(opt.value(), other.value());
+                    won > lost
+                })
+                .count() as u64
+        });
+        if let [advanced] = pool[..] { return Ok(advanced); }
+
+        let pool = Self::narrow(trace, stage, TiebreakRule::TopScoreCount, pool, |opt| {
+            u64::from(opt.metrics.count(self.max_score))
+        });
+        if let [advanced] = pool[..] { return Ok(advanced); }
+
+        if let Some(advanced) = self.draw_lot(trace, stage, pool.clone()) {
+            return Ok(advanced);
+        }
+
+        // Without a lot the tie stands, unless every tied option would lose the runoff anyway.
+        match leader {
+            Some(leader) if pool.iter().all(|opt| {
+                let (won, lost) = self.get_head_to_head_votes(leader.value(), opt.value());
+                won > lost
+            }) => Ok(Self::narrow(trace, stage, TiebreakRule::DecidedByRunoff, pool, |_| 0)[0]),
+            _ => Err(Self::tie_error(stage, &pool)),
+        }
+    }
+
+    fn leading_group<'a>(options: &[&'a VotingOption<T>]) -> Vec<&'a VotingOption<T>> {
+        options.iter()
+            .take_while(|opt| opt.metrics.total == options[0].metrics.total)
+            .copied()
+            .collect()
+    }
+
+    fn select_finalists(
+        &self,
+        trace: &mut TiebreakTrace<T>,
+        excluded: &[T],
+    ) -> Result<(&VotingOption<T>, &VotingOption<T>), VotingError<T>> {
+        let sorted = self.sort_options_by_score(excluded)?;
+        if sorted.len() < 2 { return Err(VotingError::InsufficientOptions); }
+
+        let leading = Self::leading_group(&sorted);
+        let first = match leading[..] {
+            [only] => only,
+            [a, b] => return Ok((a, b)),
+            _ => self.break_scoring_tie(trace, TiebreakStage::FirstFinalist, &leading, None)?,
+        };
+
+        let rest: Vec<_> = sorted.iter()
+            .filter(|opt| opt.value() != first.value())
+            .copied()
+            .collect();
+        let runners_up = Self::leading_group(&rest);
+        let second = match runners_up[..] {
+            [only] => only,
+            _ => self.break_scoring_tie(trace, TiebreakStage::SecondFinalist, &runners_up, Some(first))?,
+        };
+        Ok((first, second))
+    }
+
+    fn break_runoff_tie<'a>(
+        &self,
+        trace: &mut TiebreakTrace<T>,
+        finalists: [&'a VotingOption<T>; 2],
+    ) -> Result<&'a VotingOption<T>, VotingError<T>> {
+        let stage = TiebreakStage::Runoff;
+        let pool = Self::narrow(trace, stage, TiebreakRule::ScoreTotal, finalists.to_vec(), |opt| {
+            opt.metrics.total.max(0) as u64
+        });
+        if let [winner] = pool[..] { return Ok(winner); }
+
+        let pool = Self::narrow(trace, stage, TiebreakRule::TopScoreCount, pool, |opt| {
+            u64::from(opt.metrics.count(self.max_score))
+        });
+        if let [winner] = pool[..] { return Ok(winner); }
+
+        self.draw_lot(trace, stage, pool.clone()).ok_or_else(|| Self::tie_error(stage, &pool))
+    }
+
+    pub fn determine_winner(&self) -> Result<RunoffResult<T>, VotingError<T>> {
+        self.run_star(&[])
+    }
+
+    /// Re-tabulates and lists every field where `result` differs; an empty list
+    /// means it matches. Tallies don't depend on the order ballots were cast.
+    pub fn verify(&self, result: &RunoffResult<T>) -> Result<Vec<ResultMismatch<T>>, VotingError<T>> {
+        let recount = self.determine_winner()?;
+        let mut mismatches = Vec::new();
+        if recount.winner != result.winner {
+            mismatches.push(ResultMismatch::Winner {
+                stored: result.winner.clone(),
+                recounted: recount.winner.clone(),
+            });
+        }
+        if (&recount.finalist1, &recount.finalist2) != (&result.finalist1, &result.finalist2) {
+            mismatches.push(ResultMismatch::Finalists {
+                stored: (result.finalist1.clone(), result.finalist2.clone()),
+                recounted: (recount.finalist1, recount.finalist2),
+            });
+        }
+        if recount.head_to_head != result.head_to_head {
+            mismatches.push(ResultMismatch::HeadToHead {
+                stored: result.head_to_head,
+                recounted: recount.head_to_head,
+            });
+        }
+        if recount.runner_up_matchups != result.runner_up_matchups {
+            mismatches.push(ResultMismatch::RunnerUpMatchups {
+                stored: result.runner_up_matchups.clone(),
+                recounted: recount.runner_up_matchups,
+            });
+        }
+        if recount.tiebreak != result.tiebreak {
+            mismatches.push(ResultMismatch::Tiebreak {
+                stored: result.tiebreak.clone(),
+                recounted: recount.tiebreak,
+            });
+        }
+        Ok(mismatches)
+    }
+
+    /// How many ballots it would take to change `result`. Counts are in units of
+    /// ballot weight (plain ballots on unweighted votes) and assume the changed
+    /// or added ballots win outright rather than forcing a tiebreak.
+    pub fn margin_of_victory(&self, result: &RunoffResult<T>) -> Result<VictoryMargin<T>, VotingError<T>> {
+        let (winner, loser) = if result.winner == result.finalist1 {
+            (&result.finalist1, &result.finalist2)
+        } else {
+            (&result.finalist2, &result.finalist1)
+        };
+        let position = |option: &T| self.positions.get(option).copied()
+            .ok_or_else(|| VotingError::InvalidOption(option.clone()));
+        let (w, l) = (position(winner)?, position(loser)?);
+        let lead = u64::from(self.preferences[w][l].saturating_sub(self.preferences[l][w]));
+
+        Ok(VictoryMargin {
+            runoff_ballots_added: lead + 1,
+            // Each flipped ballot moves one vote from the winner to the loser.
+            runoff_ballots_changed: lead / 2 + 1,
+            finalist_margin: self.finalist_margin(w, l),
+        })
+    }
+
+    fn finalist_margin(&self, f1: usize, f2: usize) -> Option<ChallengerMargin<T>> {
+        let total = |position: usize| self.options[position].metrics.total;
+        let weakest = if total(f1) < total(f2) { f1 } else { f2 };
+        let max = i64::from(self.max_score);
+
+        (0..self.options.len())
+            .filter(|&x| x != f1 && x != f2)
+            .map(|x| {
+                let gap = (total(weakest) - total(x)).max(0);
+                ChallengerMargin {
+                    challenger: self.options[x].value().clone(),
+                    ballots_added: (gap / max + 1) as u64,
+                    ballots_changed: self.ballots_to_overtake(x, weakest, gap),
+                }
+            })
+            .min_by_key(|margin| (margin.ballots_changed.unwrap_or(u64::MAX), margin.ballots_added))
+    }
+
+    // Greedily rewrites the ballots that help most, giving the challenger the top
+    // score and the finalist 0, until the challenger's total passes the finalist's.
+    fn ballots_to_overtake(&self, challenger: usize, finalist: usize, gap: i64) -> Option<u64> {
+        let score = |group: &BallotGroup, position: usize| i64::from(group.score(position).map_or(0, Score::as_i8));
+        let mut swings: Vec<(i64, u64)> = self.groups.iter()
+            .map(|group| {
+                let swing = i64::from(self.max_score) - score(group, challenger) + score(group, finalist);
+                (swing, group.weight)
+            })
+            .filter(|&(swing, _)| swing > 0)
+            .collect();
+        swings.sort_by_key(|&(swing, _)| Reverse(swing));
+
+        let mut needed = gap + 1;
+        let mut changed = 0;
+        for (swing, weight) in swings {
+            let take = weight.min(((needed + swing - 1) / swing) as u64);
+            changed += take;
+            needed -= take as i64 * swing;
+            if needed <= 0 {
+                return Some(changed);
+            }
+        }
+        None
+    }
+
+    fn run_star(&self, excluded: &[T]) -> Result<RunoffResult<T>, VotingError<T>> {
+        let sorted = self.sort_options_by_score(excluded)?;
+        if sorted.len() < 2 { return Err(VotingError::InsufficientOptions); }
+        if self.ballot_count == 0 { return Err(VotingError::InsufficientBallots); }
+
+        let mut tiebreak = TiebreakTrace::default();
+        let (f1, f2) = self.select_finalists(&mut tiebreak, excluded)?;
+        let (p1, p2) = self.get_head_to_head_votes(f1.value(), f2.value());
+        let winner = match p1.cmp(&p2) {
+            Ordering::Greater => f1.value(),
+            Ordering::Less => f2.value(),
+            Ordering::Equal => self.break_runoff_tie(&mut tiebreak, [f1, f2])?.value(),
+        };
+
+        let runner_up_matchups = sorted.iter()
+            .filter(|opt| opt.value() != f1.value() && opt.value() != f2.value())
+            .take(RUNNER_UP_MATCHUPS)
+            .map(|runner_up| {
+                let (w, r) = self.get_head_to_head_votes(winner, runner_up.value());
+                HeadToHeadMatchup {
+                    candidate1: winner.clone(),
+                    candidate2: runner_up.value().clone(),
+                    votes1: w,
+                    votes2: r,
+                }
+            })
+            .collect();
+
+        Ok(RunoffResult {
+            winner: winner.clone(),
+            finalist1: f1.value().clone(),
+            finalist2: f2.value().clone(),
+            head_to_head: (p1, p2),
+            runner_up_matchups,
+            tiebreak,
+        })
+    }
+
+    pub fn determine_winners(
+        &self,
+        seats: usize,
+        method: MultiWinnerMethod,
+    ) -> Result<MultiWinnerResult<T>, VotingError<T>> {
+        if seats == 0 || seats > self.options.len() {
+            return Err(VotingError::InvalidSeatCount(seats));
+        }
+        if self.ballot_count == 0 { return Err(VotingError::InsufficientBallots); }
+
+        let rounds = match method {
+            MultiWinnerMethod::BlocStar => self.bloc_star_rounds(seats)?,
+            MultiWinnerMethod::AllocatedScore => self.allocated_score_rounds(seats)?,
+        };
+        Ok(MultiWinnerResult {
+            method,
+            winners: rounds.iter().map(|round| round.winner.clone()).collect(),
+            rounds,
+        })
+    }
+
+    // Sequential STAR: each place goes to the STAR winner among the options not yet placed.
+    // A tie nothing can break leaves the rest sharing one place, marked `UnresolvedTie`.
+    pub fn ranking(&self) -> Result<Vec<RankedPlace<T>>, VotingError<T>> {
+        let mut places = Vec::with_capacity(self.options.len());
+        let mut placed = Vec::with_capacity(self.options.len());
+        while placed.len() < self.options.len() {
+            let place = placed.len() + 1;
+            let remaining = self.remaining_options(&placed);
+            let runoff = match remaining[..] {
+                [only] => {
+                    places.push(RankedPlace { place, option: only.value().clone(), reason: PlaceReason::LastRemaining });
+                    break;
+                }
+                _ => self.run_star(&placed),
+            };
+            let runoff = match runoff {
+                Ok(runoff) => runoff,
+                Err(VotingError::FirstPlaceTie(tied) | VotingError::SecondPlaceTie(tied)) => {
+                    places.extend(remaining.into_iter().map(|opt| RankedPlace {
+                        place,
+                        option: opt.value().clone(),
+                        reason: PlaceReason::UnresolvedTie { tied: tied.clone() },
+                    }));
+                    break;
+                }
+                Err(e) => return Err(e),
+            };
+
+            let (opponent, votes) = if runoff.winner == runoff.finalist1 {
+                (runoff.finalist2, runoff.head_to_head)
+            } else {
+                (runoff.finalist1, (runoff.head_to_head.1, runoff.head_to_head.0))
+            };
+            let reason = match runoff.tiebreak.stage(TiebreakStage::Runoff).last() {
+                Some(step) => PlaceReason::RunoffTiebreak { opponent, rule: step.rule },
+                None => PlaceReason::WonRunoff { opponent, votes },
+            };
+            placed.push(runoff.winner.clone());
+            places.push(RankedPlace { place, option: runoff.winner, reason });
+        }
+        Ok(places)
+    }
+
+    fn bloc_star_rounds(&self, seats: usize) -> Result<Vec<WinnerRound<T>>, VotingError<T>> {
+        let mut elected = Vec::with_capacity(seats);
+        let mut rounds = Vec::with_capacity(seats);
+        for _ in 0..seats {
+            let scores = self.remaining_options(&elected)
+                .into_iter()
+                .map(|opt| (opt.value().clone(), opt.metrics.total as f64))
+                .collect::<Vec<_>>();
+            // The last remaining option of a full slate has no one left to face in a runoff.
+            let (winner, runoff) = match &scores[..] {
+                [(only, _)] => (only.clone(), None),
+                _ => {
+                    let runoff = self.run_star(&elected)?;
+                    (runoff.winner.clone(), Some(runoff))
+                }
+            };
+            elected.push(winner.clone());
+            rounds.push(WinnerRound {
+                winner,
+                scores,
+                runoff,
+                tiebreak: TiebreakTrace::default(),
+                weight_remaining: self.total_weight() as f64,
+            });
+        }
+        Ok(rounds)
+    }
+
+    // Allocated Score: each seat spends one Hare quota of ballot weight, taken from the
+    // ballots that scored the winner highest; the ballots at the cut-off score share the rest.
+    fn allocated_score_rounds(&self, seats: usize) -> Result<Vec<WinnerRound<T>>, VotingError<T>> {
+        let quota = self.total_weight() as f64 / seats as f64;
+        let mut weights: Vec<f64> = self.groups.iter().map(|group| group.weight as f64).collect();
+        let mut elected = Vec::with_capacity(seats);
+        let mut rounds = Vec::with_capacity(seats);
+        for _ in 0..seats {
+            let remaining = self.remaining_options(&elected);
+            let scores: Vec<_> = remaining.iter()
+                .map(|opt| (opt.value().clone(), self.weighted_total(self.positions[opt.value()], &weights)))
+                .collect();
+            let best = scores.iter().map(|(_, total)| *total).fold(f64::NEG_INFINITY, f64::max);
+            let tied: Vec<_> = remaining.iter()
+                .zip(&scores)
+                .filter(|(_, (_, total))| *total == best)
+                .map(|(opt, _)| *opt)
+                .collect();
+
+            let mut tiebreak = TiebreakTrace::default();
+            let winner = match tied[..] {
+                [only] => only,
+                _ => self.break_scoring_tie(&mut tiebreak, TiebreakStage::FirstFinalist, &tied, None)?,
+            }.value().clone();
+
+            self.spend_quota(&mut weights, self.positions[&winner], quota);
+            elected.push(winner.clone());
+            rounds.push(WinnerRound {
+                winner,
+                scores,
+                runoff: None,
+                tiebreak,
+                weight_remaining: weights.iter().sum(),
+            });
+        }
+        Ok(rounds)
+    }
+
+    fn total_weight(&self) -> u64 {
+        self.total_weight
+    }
+
+    fn weighted_total(&self, position: usize, weights: &[f64]) -> f64 {
+        self.groups.iter()
+            .zip(weights)
+            .filter_map(|(group, weight)| group.score(position).map(|s| f64::from(s.as_i8()) * weight))
+            .sum()
+    }
+
+    fn spend_quota(&self, weights: &mut [f64], winner: usize, quota: f64) {
+        let mut spent = 0.0;
+        for level in (1..=self.max_score).rev() {
+            let supporters: Vec<_> = self.groups.iter()
+                .enumerate()
+                .filter(|(_, group)| group.score(winner).map(|s| s.as_i8()) == Some(level))
+                .map(|(i, _)| i)
+                .collect();
+            let available: f64 = supporters.iter().map(|&i| weights[i]).sum();
+            if available <= 0.0 { continue; }
+
+            if spent + available <= quota {
+                supporters.iter().for_each(|&i| weights[i] = 0.0);
+                spent += available;
+            } else {
+                let keep = 1.0 - (quota - spent) / available;
+                supporters.iter().for_each(|&i| weights[i] *= keep);
+                return;
+            }
+        }
+    }
+
+    fn top_by<K: Ord>(&self, key: impl Fn(usize) -> K) -> Result<T, VotingError<T>> {
+        if self.options.len() < 2 { return Err(VotingError::InsufficientOptions); }
+        if self.ballot_count == 0 { return Err(VotingError::InsufficientBallots); }
+
+        let keys: Vec<_> = (0..self.options.len()).map(key).collect();
+        let best = keys.iter().max().ok_or(VotingError::InsufficientOptions)?;
+        let tied: Vec<_> = self.options.iter()
+            .zip(&keys)
+            .filter(|(_, k)| *k == best)
+            .map(|(opt, _)| opt)
+            .collect();
+        match tied[..] {
+            [only] => Ok(only.value().clone()),
+            _ => self.draw_lot(&mut TiebreakTrace::default(), TiebreakStage::FirstFinalist, tied.clone())
+                .map(|opt| opt.value().clone())
+                .ok_or_else(|| Self::tie_error(TiebreakStage::FirstFinalist, &tied)),
+        }
+    }
+
+    pub fn pairwise_analysis(&self) -> PairwiseAnalysis<T> {
+        let n = self.options.len();
+        let matrix = self.preferences.clone();
+
+        // Smith set: options that reach every other option through "beats or ties" edges.
+        let mut reaches: Vec<Vec<bool>> = (0..n)
+            .map(|i| (0..n).map(|j| i == j || matrix[i][j] >= matrix[j][i]).collect())
+            .collect();
+        for k in 0..n {
+            for i in 0..n {
+                for j in 0..n {
+                    reaches[i][j] = reaches[i][j] || (reaches[i][k] && reaches[k][j]);
+                }
+            }
+        }
+
+        let value = |i: usize| self.options[i].value().clone();
+        let condorcet_winner = (0..n)
+            .find(|&i| (0..n).all(|j| i == j || matrix[i][j] > matrix[j][i]))
+            .map(value);
+        let condorcet_loser = (0..n)
+            .find(|&i| n > 1 && (0..n).all(|j| i == j || matrix[i][j] < matrix[j][i]))
+            .map(value);
+        let smith_set = (0..n)
+            .filter(|&i| reaches[i].iter().all(|&r| r))
+            .map(value)
+            .collect();
+
+        PairwiseAnalysis {
+            options: self.options().cloned().collect(),
+            matrix,
+            condorcet_winner,
+            condorcet_loser,
+            smith_set,
+        }
+    }
+
+    /// Distinct ballots with their combined weight, scores in option order.
+    pub fn ballot_groups(&self) -> impl Iterator<Item = (&[Option<Score>], u64)> {
+        self.groups.iter().map(|group| (group.scores.as_slice(), group.weight))
+    }
+}
+
+pub trait TabulationMethod<T: Clone + Eq + Hash + Ord> {
+    fn name(&self) -> &'static str;
+    fn winner(&self, election: &Election<T>) -> Result<T, VotingError<T>>;
+}
+
+pub struct Star;
+pub struct ScoreVoting;
+pub struct Approval { pub threshold: Score }
+pub struct Plurality;
+pub struct RankedRobin;
+
+impl<T: Clone + Eq + Hash + Ord> TabulationMethod<T> for Star {
+    fn name(&self) -> &'static str { "STAR" }
+
+    fn winner(&self, election: &Election<T>) -> Result<T, VotingError<T>> {
+        election.determine_winner().map(|result| result.winner)
+    }
+}
+
+impl<T: Clone + Eq + Hash + Ord> TabulationMethod<T> for ScoreVoting {
+    fn name(&self) -> &'static str { "Score" }
+
+    fn winner(&self, election: &Election<T>) -> Result<T, VotingError<T>> {
+        election.top_by(|i| election.options[i].metrics.total)
+    }
+}
+
+impl<T: Clone + Eq + Hash + Ord> TabulationMethod<T> for Approval {
+    fn name(&self) -> &'static str { "Approval" }
+
+    fn winner(&self, election: &Election<T>) -> Result<T, VotingError<T>> {
+        election.top_by(|i| election.options[i].metrics.count_at_least(self.threshold.as_i8()))
+    }
+}
+
+impl<T: Clone + Eq + Hash + Ord> TabulationMethod<T> for Plurality {
+    fn name(&self) -> &'static str { "Plurality" }
+
+    // A ballot counts only for an option it scores strictly above every other; flat ballots abstain.
+    fn winner(&self, election: &Election<T>) -> Result<T, VotingError<T>> {
+        let mut first_choices = vec![0u64; election.options.len()];
+        for group in &election.groups {
+            let top = group.scores.iter().flatten().max();
+            let mut favourites = group.scores.iter().enumerate().filter(|(_, s)| s.is_some() && s.as_ref() == top);
+            if let (Some((position, _)), None) = (favourites.next(), favourites.next()) {
+                first_choices[position] += group.weight;
+            }
+        }
+        election.top_by(|i| first_choices[i])
+    }
+}
+
+impl<T: Clone + Eq + Hash + Ord> TabulationMethod<T> for RankedRobin {
+    fn name(&self) -> &'static str { "Ranked Robin" }
+
+    // Most head-to-head wins; ties go to the larger total margin across all matchups.
+    fn winner(&self, election: &Election<T>) -> Result<T, VotingError<T>> {
+        let matrix = election.pairwise_analysis().matrix;
+        election.top_by(|i| {
+            let wins = (0..matrix.len()).filter(|&j| matrix[i][j] > matrix[j][i]).count();
+            let margin: i64 = (0..matrix.len()).map(|j| i64::from(matrix[i][j]) - i64::from(matrix[j][i])).sum();
+            (wins, margin)
+        })
+    }
+}
+
+// Approval counts the upper half of the score range: 3+ on 0-5, 6+ on 0-10.
+pub fn comparison_methods<T: Clone + Eq + Hash + Ord>(max_score: i8) -> Vec<Box<dyn TabulationMethod<T>>> {
+    vec![
+        Box::new(Star),
+        Box::new(ScoreVoting),
+        Box::new(Approval { threshold: Score(max_score / 2 + 1) }),
+        Box::new(Plurality),
+        Box::new(RankedRobin),
+    ]
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,51 @@
+//! Run under both builds: `cargo test -p star-core` and
+//! `cargo test -p star-core --no-default-features --features alloc`.
+use alloc::vec::Vec;
+use crate::{Ballot, Election, VotingError};
+
+fn election(options: &[&'static str]) -> Election<&'static str> {
+    let mut e = Election::new();
+    options.iter().for_each(|opt| e.add_option(*opt).unwrap());
+    e
+}
+
+#[test]
+fn test_ballot_from_pairs() {
+    let from_array = Ballot::new([("A", 5), ("B", 2)]).unwrap();
+    let from_vec = Ballot::new(Vec::from([("B", 2), ("A", 5)])).unwrap();
+    assert_eq!(from_array, from_vec);
+    assert_eq!(from_array.score(&"A").map(|s| s.as_i8()), Some(5));
+    assert_eq!(from_array.score(&"C"), None);
+    assert_eq!(from_array.scores().count(), 2);
+}
+
+#[test]
+fn test_out_of_range_scores_rejected() {
+    assert!(matches!(Ballot::new([("A", -1)]), Err(VotingError::InvalidScore(-1))));
+    assert!(matches!(Ballot::new([("A", 6)]), Err(VotingError::InvalidScore(6))));
+    assert!(Ballot::with_max_score([("A", 10)], 10).is_ok());
+}
+
+#[test]
+fn test_tabulation() {
+    let mut e = election(&["A", "B", "C"]);
+    for scores in [[5, 4, 0], [4, 5, 1], [5, 3, 0], [0, 2, 5]] {
+        let ballot = Ballot::new(["A", "B", "C"].into_iter().zip(scores)).unwrap();
+        e.cast_ballot(ballot).unwrap();
+    }
+    let result = e.determine_winner().unwrap();
+    assert_eq!(result.winner, "A");
+    assert_eq!((result.finalist1, result.finalist2), ("A", "B"));
+}
+
+#[test]
+fn test_identical_ballots_share_a_group() {
+    let mut e = election(&["A", "B"]);
+    for scores in [[5, 0], [0, 5], [5, 0], [5, 0]] {
+        e.cast_ballot(Ballot::new(["A", "B"].into_iter().zip(scores)).unwrap()).unwrap();
+    }
+    let mut groups: Vec<_> = e.ballot_groups().map(|(scores, weight)| (scores.to_vec(), weight)).collect();
+    groups.sort_by_key(|(_, weight)| *weight);
+    assert_eq!(groups.iter().map(|(_, weight)| *weight).collect::<Vec<_>>(), [1, 3]);
+    assert_ne!(groups[0].0, groups[1].0);
+}
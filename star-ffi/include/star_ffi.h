@@ -0,0 +1,65 @@
+/* C interface to the STAR voting tabulator (star-ffi crate). */
+#ifndef STAR_FFI_H
+#define STAR_FFI_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* A ballot score meaning "no opinion". Any negative score is read as blank. */
+#define STAR_BLANK (-1)
+
+typedef enum StarStatus {
+    STAR_OK = 0,
+    STAR_NULL_POINTER,
+    STAR_INVALID_UTF8,
+    STAR_INVALID_SCORE,
+    STAR_INVALID_OPTION,
+    STAR_DUPLICATE_OPTION,
+    STAR_WRONG_SCORE_COUNT,
+    STAR_INSUFFICIENT_OPTIONS,
+    STAR_INSUFFICIENT_BALLOTS,
+    STAR_FIRST_PLACE_TIE,
+    STAR_SECOND_PLACE_TIE,
+    STAR_WEIGHT_OVERFLOW,
+    STAR_INVALID_SEAT_COUNT,
+    STAR_INVALID_SCORE_RANGE,
+} StarStatus;
+
+/* The runoff; options are indices in the order they were added. */
+typedef struct StarResult {
+    size_t winner;
+    size_t finalist1;
+    size_t finalist2;
+    uint32_t finalist1_votes;
+    uint32_t finalist2_votes;
+} StarResult;
+
+typedef struct StarElection StarElection;
+
+/* NULL if max_score is outside 1..=10. */
+StarElection *star_election_new(int8_t max_score);
+/* Ties no rule can break are settled by a lot drawn from seed. */
+StarElection *star_election_new_seeded(int8_t max_score, uint64_t seed);
+void star_election_free(StarElection *election);
+
+StarStatus star_election_add_option(StarElection *election, const char *name);
+size_t star_election_option_count(const StarElection *election);
+/* Valid until the election is freed; NULL if index is out of range. */
+const char *star_election_option_name(const StarElection *election, size_t index);
+
+/* One score per option, in option order. */
+StarStatus star_election_cast_ballot(StarElection *election, const int8_t *scores, size_t len);
+/* result is only written when STAR_OK is returned. */
+StarStatus star_election_determine_winner(const StarElection *election, StarResult *result);
+
+const char *star_status_message(StarStatus status);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
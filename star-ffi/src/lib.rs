@@ -0,0 +1,226 @@
+//! C ABI over `star_core::Election`, plus a Python module behind the `python`
+//! feature. Options are identified by the order they were added in; the C
+//! declarations are in `include/star_ffi.h`.
+use std::ffi::{c_char, CStr, CString};
+use std::{ptr, slice};
+use star_core::{Ballot, Election, RunoffResult, VotingError};
+
+#[cfg(feature = "python")]
+pub mod python;
+
+#[cfg(test)]
+mod tests;
+
+/// A ballot score meaning "no opinion". Any negative score is read as blank.
+pub const STAR_BLANK: i8 = -1;
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StarStatus {
+    Ok = 0,
+    NullPointer,
+    InvalidUtf8,
+    InvalidScore,
+    InvalidOption,
+    DuplicateOption,
+    WrongScoreCount,
+    InsufficientOptions,
+    InsufficientBallots,
+    FirstPlaceTie,
+    SecondPlaceTie,
+    WeightOverflow,
+    InvalidSeatCount,
+    InvalidScoreRange,
+}
+
+impl<T> From<&VotingError<T>> for StarStatus {
+    fn from(error: &VotingError<T>) -> Self {
+        match error {
+            VotingError::InvalidScore(_) => StarStatus::InvalidScore,
+            VotingError::InvalidOption(_) => StarStatus::InvalidOption,
+            VotingError::DuplicateOption(_) => StarStatus::DuplicateOption,
+            VotingError::InsufficientOptions => StarStatus::InsufficientOptions,
+            VotingError::InvalidSeatCount(_) => StarStatus::InvalidSeatCount,
+            VotingError::InvalidScoreRange(_) => StarStatus::InvalidScoreRange,
+            VotingError::InsufficientBallots => StarStatus::InsufficientBallots,
+            VotingError::FirstPlaceTie(_) => StarStatus::FirstPlaceTie,
+            VotingError::SecondPlaceTie(_) => StarStatus::SecondPlaceTie,
+            VotingError::WeightOverflow => StarStatus::WeightOverflow,
+        }
+    }
+}
+
+/// The runoff, with options given by index.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StarResult {
+    pub winner: usize,
+    pub finalist1: usize,
+    pub finalist2: usize,
+    pub finalist1_votes: u32,
+    pub finalist2_votes: u32,
+}
+
+impl StarResult {
+    fn new(election: &Election<String>, result: &RunoffResult<String>) -> Self {
+        let index = |option: &String| election.options().position(|opt| opt == option).unwrap_or_default();
+        StarResult {
+            winner: index(&result.winner),
+            finalist1: index(&result.finalist1),
+            finalist2: index(&result.finalist2),
+            finalist1_votes: result.head_to_head.0,
+            finalist2_votes: result.head_to_head.1,
+        }
+    }
+}
+
+pub struct StarElection {
+    election: Election<String>,
+    // Kept alongside the election so names can be handed out as C strings.
+    names: Vec<CString>,
+}
+
+/// Builds a ballot from one score per option, in option order; `None` is blank.
+/// Callers check the length first.
+pub(crate) fn ballot_from_scores(
+    election: &Election<String>,
+    scores: &[Option<i8>],
+) -> Result<Ballot<String>, VotingError<String>> {
+    let scores = election.options()
+        .zip(scores)
+        .filter_map(|(option, score)| score.map(|score| (option.clone(), score)));
+    Ballot::with_max_score(scores, election.max_score())
+}
+
+fn into_raw(election: Result<Election<String>, VotingError<String>>) -> *mut StarElection {
+    match election {
+        Ok(election) => Box::into_raw(Box::new(StarElection { election, names: Vec::new() })),
+        Err(_) => ptr::null_mut(),
+    }
+}
+
+/// Returns null if `max_score` is outside 1..=10. Free with `star_election_free`.
+#[no_mangle]
+pub extern "C" fn star_election_new(max_score: i8) -> *mut StarElection {
+    into_raw(Election::new().with_max_score(max_score))
+}
+
+/// Like `star_election_new`, but ties no rule can break are settled by a lot drawn from `seed`.
+#[no_mangle]
+pub extern "C" fn star_election_new_seeded(max_score: i8, seed: u64) -> *mut StarElection {
+    into_raw(Election::with_tiebreak_seed(seed).with_max_score(max_score))
+}
+
+/// # Safety
+/// `election` must come from `star_election_new*` and must not be used afterwards. Null is ignored.
+#[no_mangle]
+pub unsafe extern "C" fn star_election_free(election: *mut StarElection) {
+    if !election.is_null() {
+        drop(Box::from_raw(election));
+    }
+}
+
+/// # Safety
+/// `election` must be a live election and `name` a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn star_election_add_option(election: *mut StarElection, name: *const c_char) -> StarStatus {
+    let Some(election) = election.as_mut() else { return StarStatus::NullPointer };
+    if name.is_null() {
+        return StarStatus::NullPointer;
+    }
+    let name = CStr::from_ptr(name);
+    let Ok(option) = name.to_str() else { return StarStatus::InvalidUtf8 };
+    match election.election.add_option(option.to_owned()) {
+        Ok(()) => {
+            election.names.push(name.to_owned());
+            StarStatus::Ok
+        }
+        Err(e) => StarStatus::from(&e),
+    }
+}
+
+/// # Safety
+/// `election` must be a live election or null.
+#[no_mangle]
+pub unsafe extern "C" fn star_election_option_count(election: *const StarElection) -> usize {
+    election.as_ref().map_or(0, |election| election.names.len())
+}
+
+/// The name of the option at `index`, valid until the election is freed; null if out of range.
+///
+/// # Safety
+/// `election` must be a live election or null.
+#[no_mangle]
+pub unsafe extern "C" fn star_election_option_name(election: *const StarElection, index: usize) -> *const c_char {
+    election.as_ref()
+        .and_then(|election| election.names.get(index))
+        .map_or(ptr::null(), |name| name.as_ptr())
+}
+
+/// Casts a ballot of `len` scores, one per option in option order. Negative scores are blank.
+///
+/// # Safety
+/// `election` must be a live election and `scores` must point to `len` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn star_election_cast_ballot(
+    election: *mut StarElection,
+    scores: *const i8,
+    len: usize,
+) -> StarStatus {
+    let Some(election) = election.as_mut() else { return StarStatus::NullPointer };
+    if len != election.names.len() {
+        return StarStatus::WrongScoreCount;
+    }
+    if len > 0 && scores.is_null() {
+        return StarStatus::NullPointer;
+    }
+    let scores: Vec<_> = if len == 0 { Vec::new() } else { slice::from_raw_parts(scores, len).to_vec() };
+    let scores: Vec<_> = scores.into_iter().map(|score| (score >= 0).then_some(score)).collect();
+    match ballot_from_scores(&election.election, &scores).and_then(|ballot| election.election.cast_ballot(ballot)) {
+        Ok(()) => StarStatus::Ok,
+        Err(e) => StarStatus::from(&e),
+    }
+}
+
+/// Runs the STAR tabulation. `result` is only written when `StarStatus::Ok` is returned.
+///
+/// # Safety
+/// `election` must be a live election and `result` writable.
+#[no_mangle]
+pub unsafe extern "C" fn star_election_determine_winner(
+    election: *const StarElection,
+    result: *mut StarResult,
+) -> StarStatus {
+    let (Some(election), Some(result)) = (election.as_ref(), result.as_mut()) else {
+        return StarStatus::NullPointer;
+    };
+    match election.election.determine_winner() {
+        Ok(runoff) => {
+            *result = StarResult::new(&election.election, &runoff);
+            StarStatus::Ok
+        }
+        Err(e) => StarStatus::from(&e),
+    }
+}
+
+/// A static, human-readable description of `status`.
+#[no_mangle]
+pub extern "C" fn star_status_message(status: StarStatus) -> *const c_char {
+    let message = match status {
+        StarStatus::Ok => c"Ok",
+        StarStatus::NullPointer => c"Null pointer",
+        StarStatus::InvalidUtf8 => c"Option name is not valid UTF-8",
+        StarStatus::InvalidScore => c"Score outside the election's score range",
+        StarStatus::InvalidOption => c"Invalid option",
+        StarStatus::DuplicateOption => c"Duplicate option",
+        StarStatus::WrongScoreCount => c"Ballot must have one score per option",
+        StarStatus::InsufficientOptions => c"Need at least 2 options",
+        StarStatus::InsufficientBallots => c"No ballots cast",
+        StarStatus::FirstPlaceTie => c"Perfect tie for first",
+        StarStatus::SecondPlaceTie => c"Tie for second",
+        StarStatus::WeightOverflow => c"Too many ballots",
+        StarStatus::InvalidSeatCount => c"Seat count must be between 1 and the number of options",
+        StarStatus::InvalidScoreRange => c"Maximum score must be between 1 and 10",
+    };
+    message.as_ptr()
+}
@@ -0,0 +1,70 @@
+//! The `star_voting` Python module. Errors are raised as `ValueError` with the
+//! same message the Rust API reports.
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use star_core::{Election, VotingError, DEFAULT_MAX_SCORE};
+use crate::ballot_from_scores;
+
+fn value_error(error: VotingError<String>) -> PyErr {
+    PyValueError::new_err(error.to_string())
+}
+
+#[pyclass(name = "Election", module = "star_voting")]
+pub struct PyElection {
+    election: Election<String>,
+}
+
+#[pymethods]
+impl PyElection {
+    #[new]
+    #[pyo3(signature = (max_score = DEFAULT_MAX_SCORE, tiebreak_seed = None))]
+    fn new(max_score: i8, tiebreak_seed: Option<u64>) -> PyResult<Self> {
+        tiebreak_seed.map_or_else(Election::new, Election::with_tiebreak_seed)
+            .with_max_score(max_score)
+            .map(|election| PyElection { election })
+            .map_err(value_error)
+    }
+
+    #[getter]
+    fn options(&self) -> Vec<String> {
+        self.election.options().cloned().collect()
+    }
+
+    fn add_option(&mut self, option: String) -> PyResult<()> {
+        self.election.add_option(option).map_err(value_error)
+    }
+
+    /// One score per option, in the order they were added; `None` is blank.
+    fn cast_ballot(&mut self, scores: Vec<Option<i8>>) -> PyResult<()> {
+        let expected = self.election.options().count();
+        if scores.len() != expected {
+            return Err(PyValueError::new_err(format!("Expected {expected} scores, got {}", scores.len())));
+        }
+        let ballot = ballot_from_scores(&self.election, &scores).map_err(value_error)?;
+        self.election.cast_ballot(ballot).map_err(value_error)
+    }
+
+    fn determine_winner(&self) -> PyResult<PyRunoffResult> {
+        let result = self.election.determine_winner().map_err(value_error)?;
+        Ok(PyRunoffResult {
+            winner: result.winner,
+            finalist1: result.finalist1,
+            finalist2: result.finalist2,
+            head_to_head: result.head_to_head,
+        })
+    }
+}
+
+#[pyclass(name = "RunoffResult", module = "star_voting", frozen, get_all)]
+pub struct PyRunoffResult {
+    winner: String,
+    finalist1: String,
+    finalist2: String,
+    head_to_head: (u32, u32),
+}
+
+#[pymodule]
+pub fn star_voting(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_class::<PyElection>()?;
+    module.add_class::<PyRunoffResult>()
+}
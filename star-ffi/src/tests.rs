@@ -0,0 +1,151 @@
+use std::ffi::{CStr, CString};
+use star_core::{Ballot, Election, RunoffResult, VotingError};
+use crate::*;
+
+struct Scenario {
+    name: &'static str,
+    options: &'static [&'static str],
+    ballots: &'static [&'static [i8]],
+    seed: Option<u64>,
+}
+
+const fn scenario(name: &'static str, options: &'static [&'static str], ballots: &'static [&'static [i8]]) -> Scenario {
+    Scenario { name, options, ballots, seed: None }
+}
+
+// The ballots of the single-winner scenarios in shared/src/tests.rs, in option order.
+const SCENARIOS: &[Scenario] = &[
+    scenario("perfect tie", &["A", "B"], &[&[5, 5], &[5, 5]]),
+    scenario("tied runners-up", &["A", "B", "C"], &[&[5, 3, 3], &[4, 3, 3], &[4, 3, 3], &[0, 3, 3]]),
+    scenario("winner determination", &["A", "B", "C"], &[&[5, 4, 0], &[4, 3, 0], &[4, 3, 0], &[3, 2, 0]]),
+    scenario("single ballot", &["A", "B"], &[&[5, 0]]),
+    scenario("varied scores", &["A", "B", "C"], &[&[5, 3, 1], &[3, 5, 2], &[2, 4, 5], &[5, 2, 3]]),
+    scenario("large tie", &["A", "B", "C", "D"], &[&[5, 5, 5, 5], &[5, 5, 5, 5]]),
+    scenario("close runoff", &["A", "B", "C"], &[&[4, 4, 0], &[4, 3, 0], &[3, 5, 1]]),
+    scenario("different ordering", &["X", "Y", "Z"], &[&[5, 4, 1], &[3, 5, 2], &[4, 2, 5]]),
+    scenario("all zero", &["A", "B", "C"], &[&[0, 0, 0]]),
+    scenario("identical ballots", &["A", "B"], &[&[3, 2], &[3, 2], &[3, 2]]),
+    scenario("head-to-head tiebreak", &["A", "B", "C"], &[&[0, 1, 0], &[0, 1, 0], &[4, 0, 4], &[0, 2, 0]]),
+    scenario("five-star tiebreak", &["X", "A", "B"], &[&[5, 5, 1], &[5, 0, 4]]),
+    Scenario { name: "seeded lot", options: &["A", "B", "C", "D"], ballots: &[&[5, 5, 5, 5]], seed: Some(7) },
+    scenario("runoff tie by score", &["A", "B"], &[&[5, 0], &[3, 4]]),
+    scenario("blank scores", &["A", "B"], &[&[2, STAR_BLANK], &[STAR_BLANK, 1]]),
+    scenario("no ballots", &["A", "B"], &[]),
+    scenario("one option", &["A"], &[&[5]]),
+];
+
+fn rust_result(scenario: &Scenario) -> Result<RunoffResult<String>, VotingError<String>> {
+    let mut election = scenario.seed.map_or_else(Election::new, Election::with_tiebreak_seed);
+    scenario.options.iter().for_each(|opt| election.add_option(opt.to_string()).unwrap());
+    for scores in scenario.ballots {
+        let scores = scenario.options.iter()
+            .zip(scores.iter())
+            .filter(|(_, score)| **score >= 0)
+            .map(|(opt, score)| (opt.to_string(), *score));
+        election.cast_ballot(Ballot::new(scores).unwrap()).unwrap();
+    }
+    election.determine_winner()
+}
+
+#[test]
+fn test_c_abi_matches_rust_api() {
+    for scenario in SCENARIOS {
+        let name = scenario.name;
+        unsafe {
+            let election = match scenario.seed {
+                Some(seed) => star_election_new_seeded(5, seed),
+                None => star_election_new(5),
+            };
+            for option in scenario.options {
+                let option = CString::new(*option).unwrap();
+                assert_eq!(star_election_add_option(election, option.as_ptr()), StarStatus::Ok, "{name}");
+            }
+            for scores in scenario.ballots {
+                assert_eq!(star_election_cast_ballot(election, scores.as_ptr(), scores.len()), StarStatus::Ok, "{name}");
+            }
+
+            let mut result = StarResult::default();
+            let status = star_election_determine_winner(election, &mut result);
+            let option_name = |index| CStr::from_ptr(star_election_option_name(election, index)).to_str().unwrap();
+            match rust_result(scenario) {
+                Ok(expected) => {
+                    assert_eq!(status, StarStatus::Ok, "{name}");
+                    assert_eq!(option_name(result.winner), expected.winner, "{name}");
+                    assert_eq!(option_name(result.finalist1), expected.finalist1, "{name}");
+                    assert_eq!(option_name(result.finalist2), expected.finalist2, "{name}");
+                    assert_eq!((result.finalist1_votes, result.finalist2_votes), expected.head_to_head, "{name}");
+                }
+                Err(e) => assert_eq!(status, StarStatus::from(&e), "{name}"),
+            }
+            star_election_free(election);
+        }
+    }
+}
+
+#[test]
+fn test_c_abi_rejects_bad_input() {
+    unsafe {
+        assert!(star_election_new(0).is_null());
+        assert!(star_election_new(11).is_null());
+
+        let election = star_election_new(3);
+        let (a, b) = (CString::new("A").unwrap(), CString::new("B").unwrap());
+        assert_eq!(star_election_add_option(election, a.as_ptr()), StarStatus::Ok);
+        assert_eq!(star_election_add_option(election, a.as_ptr()), StarStatus::DuplicateOption);
+        assert_eq!(star_election_add_option(election, b.as_ptr()), StarStatus::Ok);
+        assert_eq!(star_election_add_option(election, std::ptr::null()), StarStatus::NullPointer);
+        assert_eq!(star_election_option_count(election), 2);
+        assert!(star_election_option_name(election, 2).is_null());
+
+        assert_eq!(star_election_cast_ballot(election, [1].as_ptr(), 1), StarStatus::WrongScoreCount);
+        assert_eq!(star_election_cast_ballot(election, [1, 4].as_ptr(), 2), StarStatus::InvalidScore);
+        assert_eq!(star_election_cast_ballot(election, std::ptr::null(), 2), StarStatus::NullPointer);
+
+        let mut result = StarResult::default();
+        assert_eq!(star_election_determine_winner(election, &mut result), StarStatus::InsufficientBallots);
+        assert_eq!(star_election_determine_winner(election, std::ptr::null_mut()), StarStatus::NullPointer);
+        let message = CStr::from_ptr(star_status_message(StarStatus::InsufficientBallots));
+        assert_eq!(message.to_str().unwrap(), "No ballots cast");
+        star_election_free(election);
+    }
+}
+
+#[cfg(feature = "python")]
+#[test]
+fn test_python_module_matches_rust_api() {
+    use pyo3::prelude::*;
+    use pyo3::types::{PyDict, PyModule};
+
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| -> PyResult<()> {
+        let module = PyModule::new(py, "star_voting")?;
+        python::star_voting(&module)?;
+        for scenario in SCENARIOS {
+            let name = scenario.name;
+            let kwargs = PyDict::new(py);
+            kwargs.set_item("tiebreak_seed", scenario.seed)?;
+            let election = module.getattr("Election")?.call((), Some(&kwargs))?;
+            for option in scenario.options {
+                election.call_method1("add_option", (*option,))?;
+            }
+            for scores in scenario.ballots {
+                let scores: Vec<_> = scores.iter().map(|&score| (score >= 0).then_some(score)).collect();
+                election.call_method1("cast_ballot", (scores,))?;
+            }
+
+            let result = election.call_method0("determine_winner");
+            match (rust_result(scenario), result) {
+                (Ok(expected), Ok(result)) => {
+                    assert_eq!(result.getattr("winner")?.extract::<String>()?, expected.winner, "{name}");
+                    assert_eq!(result.getattr("finalist1")?.extract::<String>()?, expected.finalist1, "{name}");
+                    assert_eq!(result.getattr("finalist2")?.extract::<String>()?, expected.finalist2, "{name}");
+                    assert_eq!(result.getattr("head_to_head")?.extract::<(u32, u32)>()?, expected.head_to_head, "{name}");
+                }
+                (Err(expected), Err(error)) => assert_eq!(error.value(py).to_string(), expected.to_string(), "{name}"),
+                (expected, result) => panic!("{name}: expected {expected:?}, got {result:?}"),
+            }
+        }
+        Ok(())
+    })
+    .unwrap();
+}